@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+// domain separation keeps a leaf from being passed off as an inner node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Leaf of the asset allowlist tree for `asset_id`.
+pub fn leaf_hash(asset_id: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, asset_id.as_ref()]).to_bytes()
+}
+
+/// Inner node over two children. Children are sorted so proofs do not need
+/// to carry left/right flags.
+pub fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Checks `proof` links `asset_id` to `root`.
+pub fn verify_proof(root: &[u8; 32], asset_id: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(leaf_hash(asset_id), |node, sibling| {
+        node_hash(&node, sibling)
+    });
+    computed == *root
+}
+
+/// Off-chain builder for the asset allowlist tree and its proofs.
+#[cfg(not(target_os = "solana"))]
+pub struct AssetAllowlist {
+    // levels[0] holds the leaves, the last level holds the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl AssetAllowlist {
+    pub fn new(asset_ids: &[Pubkey]) -> Self {
+        let mut levels = vec![asset_ids.iter().map(leaf_hash).collect::<Vec<_>>()];
+
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            // an unpaired node is promoted to the next level as is
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node_hash(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Self { levels }
+    }
+
+    /// Root to store as `HybridPoolConfig.asset_allowlist_root`.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Proof for `asset_id`, or `None` if it is not on the allowlist.
+    pub fn proof(&self, asset_id: &Pubkey) -> Option<Vec<[u8; 32]>> {
+        let leaf = leaf_hash(asset_id);
+        let mut index = self.levels[0].iter().position(|node| *node == leaf)?;
+
+        let mut proof = vec![];
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+
+        Some(proof)
+    }
+}
@@ -0,0 +1,216 @@
+use crate::errors::ErrorCode;
+use crate::state::NftStore;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+
+/// Accounts of one leaf in a batch: its nft store, then its proof nodes.
+pub struct LeafAccounts<'a, 'info> {
+    pub nft_store: &'a AccountInfo<'info>,
+    pub proof: &'a [AccountInfo<'info>],
+}
+
+/// Splits `accounts` into one group per leaf, leaf `i` taking its nft store
+/// followed by `proof_lengths[i]` proof nodes. Every account must be used.
+pub fn split_leaf_accounts<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+    proof_lengths: &[u8],
+) -> Result<Vec<LeafAccounts<'a, 'info>>> {
+    let mut rest = accounts;
+    let mut leaves = Vec::with_capacity(proof_lengths.len());
+    for &proof_length in proof_lengths {
+        let Some((nft_store, tail)) = rest.split_first() else {
+            return Err(error!(ErrorCode::InvalidLeafAccounts));
+        };
+        if tail.len() < proof_length as usize {
+            return Err(error!(ErrorCode::InvalidLeafAccounts));
+        }
+        let (proof, tail) = tail.split_at(proof_length as usize);
+        leaves.push(LeafAccounts { nft_store, proof });
+        rest = tail;
+    }
+
+    if !rest.is_empty() {
+        return Err(error!(ErrorCode::InvalidLeafAccounts));
+    }
+
+    Ok(leaves)
+}
+
+/// Checks `nft_store` is the store PDA of `pos` in `pool`.
+pub fn verify_nft_store_address(
+    nft_store: &Pubkey,
+    pool: &Pubkey,
+    pos: u8,
+    bump: u8,
+) -> Result<()> {
+    let seeds = &[b"cnft".as_ref(), pool.as_ref(), &pos.to_le_bytes(), &[bump]];
+    let expected = Pubkey::create_program_address(seeds, &crate::ID)
+        .map_err(|_| error!(ErrorCode::InvalidNftStore))?;
+    if expected != *nft_store {
+        return Err(error!(ErrorCode::InvalidNftStore));
+    }
+
+    Ok(())
+}
+
+/// Creates the store PDA of `pos` in `pool` and writes `store` to it. Like
+/// the `init` constraint, a PDA someone already sent lamports to is topped up
+/// to rent exemption and then allocated and assigned, so pre-funding a store
+/// address cannot block the deposit.
+pub fn init_nft_store<'info>(
+    nft_store: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    pool: &Pubkey,
+    pos: u8,
+    store: impl FnOnce(u8) -> NftStore,
+) -> Result<()> {
+    let pos_bytes = pos.to_le_bytes();
+    let (expected, bump) =
+        Pubkey::find_program_address(&[b"cnft".as_ref(), pool.as_ref(), &pos_bytes], &crate::ID);
+    if expected != *nft_store.key {
+        return Err(error!(ErrorCode::InvalidNftStore));
+    }
+
+    let nft_store_seeds = &[b"cnft".as_ref(), pool.as_ref(), &pos_bytes, &[bump]];
+    let nft_store_signer = &[&nft_store_seeds[..]];
+    let rent = Rent::get()?.minimum_balance(NftStore::LEN);
+    let lamports = nft_store.lamports();
+    if lamports == 0 {
+        let create_accounts = CreateAccount {
+            from: payer.clone(),
+            to: nft_store.clone(),
+        };
+        system_program::create_account(
+            CpiContext::new_with_signer(system_program.clone(), create_accounts, nft_store_signer),
+            rent,
+            NftStore::LEN as u64,
+            &crate::ID,
+        )?;
+    } else {
+        let top_up = rent.saturating_sub(lamports);
+        if top_up > 0 {
+            let transfer_accounts = Transfer {
+                from: payer.clone(),
+                to: nft_store.clone(),
+            };
+            system_program::transfer(
+                CpiContext::new(system_program.clone(), transfer_accounts),
+                top_up,
+            )?;
+        }
+
+        let allocate_accounts = Allocate {
+            account_to_allocate: nft_store.clone(),
+        };
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                allocate_accounts,
+                nft_store_signer,
+            ),
+            NftStore::LEN as u64,
+        )?;
+
+        let assign_accounts = Assign {
+            account_to_assign: nft_store.clone(),
+        };
+        system_program::assign(
+            CpiContext::new_with_signer(system_program.clone(), assign_accounts, nft_store_signer),
+            &crate::ID,
+        )?;
+    }
+
+    let mut data = nft_store.try_borrow_mut_data()?;
+    store(bump).try_serialize(&mut &mut data[..])
+}
+
+/// Largest serialized transaction a validator accepts.
+#[cfg(not(target_os = "solana"))]
+pub const PACKET_DATA_SIZE: usize = 1232;
+/// Accounts a transaction can lock, the program included.
+#[cfg(not(target_os = "solana"))]
+pub const MAX_ACCOUNT_LOCKS: usize = 64;
+/// Compute units a transaction can request.
+#[cfg(not(target_os = "solana"))]
+const MAX_COMPUTE_UNITS: usize = 1_400_000;
+/// Compute budgeted per deposited leaf. The Bubblegum transfer replaying
+/// the proof is the bulk of it, the leaf hashes, the allowlist proof and the
+/// store creation add the rest.
+#[cfg(not(target_os = "solana"))]
+const LEAF_COMPUTE_UNITS: usize = 175_000;
+/// Accounts `deposit_cnfts` takes before the remaining accounts.
+#[cfg(not(target_os = "solana"))]
+pub const DEPOSIT_CNFTS_ACCOUNTS: usize = 12;
+/// Bytes of a `deposit_cnfts` transaction besides the leaf args and the
+/// account indexes: the signature, the version and header, the signer and
+/// program keys, the blockhash, the instruction framing, the discriminator
+/// and vector lengths of the args, and the lookup table framing.
+#[cfg(not(target_os = "solana"))]
+const DEPOSIT_CNFTS_OVERHEAD: usize = 65 + 1 + 3 + 65 + 32 + 5 + 16 + 35;
+
+/// Limits a batch of leaves has to fit in. Every account but the signer and
+/// the program is assumed to come from one address lookup table, so each
+/// costs its lookup index and its instruction index, two bytes of the
+/// transaction.
+#[cfg(not(target_os = "solana"))]
+pub struct BatchLimits {
+    /// Leaves per transaction, bounded by compute.
+    pub max_leaves: usize,
+    /// Remaining accounts per transaction, bounded by the account lock limit.
+    pub max_accounts: usize,
+    /// Serialized leaf args per transaction, bounded by the transaction size.
+    pub max_data_len: usize,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl Default for BatchLimits {
+    /// The most a single `deposit_cnfts` transaction takes, leaving nothing
+    /// for other instructions.
+    fn default() -> Self {
+        let max_accounts = MAX_ACCOUNT_LOCKS - DEPOSIT_CNFTS_ACCOUNTS - 1;
+        // the signer is a static key, so it has no lookup index
+        let account_index_len = 2 * (DEPOSIT_CNFTS_ACCOUNTS + max_accounts) - 1;
+        Self {
+            max_leaves: MAX_COMPUTE_UNITS / LEAF_COMPUTE_UNITS,
+            max_accounts,
+            max_data_len: PACKET_DATA_SIZE - DEPOSIT_CNFTS_OVERHEAD - account_index_len,
+        }
+    }
+}
+
+/// Off-chain split of a deposit into batches within `limits`. Returns the
+/// ranges of `leaves` and `proof_lengths` to send per `deposit_cnfts`. A leaf
+/// over the limits on its own still gets a batch, which will fail on chain.
+#[cfg(not(target_os = "solana"))]
+pub fn chunk_deposits(
+    leaves: &[crate::instructions::DepositCnftArgs],
+    proof_lengths: &[u8],
+    limits: &BatchLimits,
+) -> Vec<std::ops::Range<usize>> {
+    let mut chunks = vec![];
+    let mut start = 0;
+    let (mut accounts, mut data_len) = (0, 0);
+    for (index, (leaf, &proof_length)) in leaves.iter().zip(proof_lengths).enumerate() {
+        let leaf_accounts = 1 + proof_length as usize;
+        // the proof length travels with each leaf
+        let leaf_data_len = leaf.try_to_vec().unwrap_or_default().len() + 1;
+
+        let fits = index - start < limits.max_leaves
+            && accounts + leaf_accounts <= limits.max_accounts
+            && data_len + leaf_data_len <= limits.max_data_len;
+        if !fits && index > start {
+            chunks.push(start..index);
+            start = index;
+            (accounts, data_len) = (0, 0);
+        }
+        accounts += leaf_accounts;
+        data_len += leaf_data_len;
+    }
+    let count = leaves.len().min(proof_lengths.len());
+    if start < count {
+        chunks.push(start..count);
+    }
+
+    chunks
+}
@@ -0,0 +1,421 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::validation::{verify_allowlisted_asset, verify_leaf};
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::{
+    BurnCpi, BurnCpiAccounts, BurnInstructionArgs, CreateTreeConfigCpi,
+    CreateTreeConfigCpiAccounts, CreateTreeConfigInstructionArgs, MintToCollectionV1Cpi,
+    MintToCollectionV1CpiAccounts, MintToCollectionV1InstructionArgs, MintV1Cpi, MintV1CpiAccounts,
+    MintV1InstructionArgs, TransferCpi, TransferCpiAccounts, TransferInstructionArgs,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use mpl_bubblegum::types::MetadataArgs;
+use mpl_bubblegum::utils::get_asset_id;
+
+pub mod token_metadata {
+    anchor_lang::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+/// Tree and program accounts shared by every Bubblegum CPI. They are passed
+/// or left out as a whole, so instructions that only sometimes call Bubblegum
+/// can skip them; the CPI helpers fail unless every account is present.
+#[derive(Accounts)]
+pub struct BubblegumAccounts<'info> {
+    // a tree authority without its tree fails the seeds check
+    #[account(
+        seeds = [merkle_tree.as_ref().map(Key::key).unwrap_or_default().as_ref()],
+        bump,
+        seeds::program = mpl_bubblegum::ID
+    )]
+    /// CHECK: Checked in CPI
+    pub tree_authority: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    ///CHECK: Checked in CPI
+    pub merkle_tree: Option<UncheckedAccount<'info>>,
+    #[account(address = SPL_NOOP_ID)]
+    ///CHECK: Noop program
+    pub log_wrapper: Option<UncheckedAccount<'info>>,
+    #[account(address = mpl_bubblegum::ID)]
+    ///CHECK: Bubblegum program
+    pub bubblegum_program: Option<UncheckedAccount<'info>>,
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    /// CHECK: Account compression program
+    pub compression_program: Option<UncheckedAccount<'info>>,
+}
+
+/// The accounts of a `BubblegumAccounts` that was passed in.
+struct Tree<'a, 'info> {
+    tree_authority: &'a UncheckedAccount<'info>,
+    merkle_tree: &'a UncheckedAccount<'info>,
+    log_wrapper: &'a UncheckedAccount<'info>,
+    bubblegum_program: &'a UncheckedAccount<'info>,
+    compression_program: &'a UncheckedAccount<'info>,
+}
+
+impl<'info> BubblegumAccounts<'info> {
+    fn tree(&self) -> Result<Tree<'_, 'info>> {
+        let (
+            Some(tree_authority),
+            Some(merkle_tree),
+            Some(log_wrapper),
+            Some(bubblegum_program),
+            Some(compression_program),
+        ) = (
+            &self.tree_authority,
+            &self.merkle_tree,
+            &self.log_wrapper,
+            &self.bubblegum_program,
+            &self.compression_program,
+        )
+        else {
+            return Err(error!(ErrorCode::MissingBubblegumAccounts));
+        };
+
+        Ok(Tree {
+            tree_authority,
+            merkle_tree,
+            log_wrapper,
+            bubblegum_program,
+            compression_program,
+        })
+    }
+
+    /// Key of the tree the leaves live in.
+    pub fn merkle_tree_key(&self) -> Result<Pubkey> {
+        Ok(self.tree()?.merkle_tree.key())
+    }
+
+    /// Creates a private tree with the pool as tree creator. The merkle tree
+    /// account is allocated by the caller, it is too big to be created
+    /// through a CPI.
+    pub fn create_tree(
+        &self,
+        pool: &Account<'info, HybridPoolConfig>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        let tree = self.tree()?;
+        if *tree.merkle_tree.owner != SPL_ACCOUNT_COMPRESSION_ID {
+            return Err(error!(anchor_lang::error::ErrorCode::ConstraintOwner));
+        }
+
+        let pool_authority = pool.authority.key();
+        let pool_seeds = &[b"pool".as_ref(), pool_authority.as_ref(), &[pool.bump]];
+        let pool_signer = &[&pool_seeds[..]];
+
+        CreateTreeConfigCpi::new(
+            tree.bubblegum_program,
+            CreateTreeConfigCpiAccounts {
+                tree_config: tree.tree_authority,
+                merkle_tree: tree.merkle_tree,
+                payer,
+                tree_creator: &pool.to_account_info(),
+                log_wrapper: tree.log_wrapper,
+                compression_program: tree.compression_program,
+                system_program,
+            },
+            CreateTreeConfigInstructionArgs {
+                max_depth,
+                max_buffer_size,
+                public: Some(false),
+            },
+        )
+        .invoke_signed(pool_signer)?;
+
+        Ok(())
+    }
+
+    /// Moves the leaf to `new_leaf_owner`. The proof nodes come in as
+    /// `proof`, and `signer_seeds` signs for a PDA `leaf_owner`.
+    pub fn transfer(
+        &self,
+        leaf_owner: &AccountInfo<'info>,
+        new_leaf_owner: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        args: TransferInstructionArgs,
+        proof: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let tree = self.tree()?;
+        TransferCpi::new(
+            tree.bubblegum_program,
+            TransferCpiAccounts {
+                tree_config: tree.tree_authority,
+                leaf_owner: (leaf_owner, true),
+                leaf_delegate: (leaf_owner, false),
+                new_leaf_owner,
+                merkle_tree: tree.merkle_tree,
+                log_wrapper: tree.log_wrapper,
+                compression_program: tree.compression_program,
+                system_program,
+            },
+            args,
+        )
+        .invoke_signed_with_remaining_accounts(
+            signer_seeds,
+            proof
+                .iter()
+                .map(|account| (account, false, false))
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Burns the leaf owned by the signing `leaf_owner`.
+    pub fn burn(
+        &self,
+        leaf_owner: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        args: BurnInstructionArgs,
+        proof: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let tree = self.tree()?;
+        BurnCpi::new(
+            tree.bubblegum_program,
+            BurnCpiAccounts {
+                tree_config: tree.tree_authority,
+                leaf_owner: (leaf_owner, true),
+                leaf_delegate: (leaf_owner, false),
+                merkle_tree: tree.merkle_tree,
+                log_wrapper: tree.log_wrapper,
+                compression_program: tree.compression_program,
+                system_program,
+            },
+            args,
+        )
+        .invoke_with_remaining_accounts(
+            proof
+                .iter()
+                .map(|account| (account, false, false))
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )?;
+
+        Ok(())
+    }
+}
+
+/// A cnft leaf as described by the caller, with the proof nodes Bubblegum
+/// checks it against.
+pub struct CnftLeaf<'a, 'info> {
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+    pub metadata: MetadataArgs,
+    /// Proof for the asset id, only needed when the pool has an asset allowlist.
+    pub asset_proof: Vec<[u8; 32]>,
+    pub proof: &'a [AccountInfo<'info>],
+}
+
+impl<'info> BubblegumAccounts<'info> {
+    /// Checks the leaf hashes against its metadata and that the pool admits
+    /// the cnft, returning its asset id.
+    pub fn admit_cnft(&self, pool: &HybridPoolConfig, leaf: &CnftLeaf) -> Result<Pubkey> {
+        verify_leaf(&leaf.metadata, &leaf.data_hash, &leaf.creator_hash, pool)?;
+
+        let asset_id = get_asset_id(&self.merkle_tree_key()?, leaf.nonce);
+        verify_allowlisted_asset(&asset_id, &leaf.asset_proof, pool)?;
+
+        Ok(asset_id)
+    }
+
+    /// Checks `nft_store` recorded the leaf with `nonce` in this tree. Anyone
+    /// can send a store more cnfts, and only the recorded one belongs to the
+    /// pool position.
+    pub fn verify_stored_leaf(&self, nft_store: &NftStore, nonce: u64) -> Result<()> {
+        if nft_store.asset_id != get_asset_id(&self.merkle_tree_key()?, nonce) {
+            return Err(error!(ErrorCode::LeafNotInTree));
+        }
+
+        Ok(())
+    }
+
+    /// Admits the cnft and moves it from `owner` into the freshly created
+    /// `nft_store`, recording it there. Deposits and sells of a held cnft
+    /// start here.
+    pub fn store_cnft(
+        &self,
+        pool: &HybridPoolConfig,
+        leaf: &CnftLeaf<'_, 'info>,
+        owner: &Signer<'info>,
+        nft_store: &mut Account<'info, NftStore>,
+        nft_store_bump: u8,
+        system_program: &Program<'info, System>,
+    ) -> Result<()> {
+        let asset_id = self.take_cnft(
+            pool,
+            leaf,
+            &owner.to_account_info(),
+            &nft_store.to_account_info(),
+            &system_program.to_account_info(),
+        )?;
+        nft_store.set_inner(NftStore {
+            asset_id,
+            bump: nft_store_bump,
+            kind: AssetKind::Compressed,
+        });
+
+        Ok(())
+    }
+
+    /// Admits the cnft and moves it from `owner` to `nft_store`, returning
+    /// its asset id for the caller to record in the store.
+    pub fn take_cnft(
+        &self,
+        pool: &HybridPoolConfig,
+        leaf: &CnftLeaf<'_, 'info>,
+        owner: &AccountInfo<'info>,
+        nft_store: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<Pubkey> {
+        let asset_id = self.admit_cnft(pool, leaf)?;
+
+        self.transfer(
+            owner,
+            nft_store,
+            system_program,
+            TransferInstructionArgs {
+                root: leaf.root,
+                data_hash: leaf.data_hash,
+                creator_hash: leaf.creator_hash,
+                nonce: leaf.nonce,
+                index: leaf.index,
+            },
+            leaf.proof,
+            &[],
+        )?;
+
+        Ok(asset_id)
+    }
+}
+
+/// Collection accounts for minting into the pool's first collection, passed
+/// as a whole when the pool has a collection and left out otherwise. The
+/// collection authority record is only needed when the pool is a delegated
+/// collection authority.
+#[derive(Accounts)]
+pub struct CollectionAccounts<'info> {
+    ///CHECK: Checked against the pool collections when minting
+    pub collection_mint: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    ///CHECK: Checked in CPI
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+    ///CHECK: Checked in CPI
+    pub collection_edition: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked in CPI
+    pub collection_authority_record: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [b"collection_cpi".as_ref()],
+        bump,
+        seeds::program = mpl_bubblegum::ID
+    )]
+    ///CHECK: Bubblegum signer PDA
+    pub bubblegum_signer: Option<UncheckedAccount<'info>>,
+    #[account(address = token_metadata::ID)]
+    ///CHECK: Token metadata program
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> BubblegumAccounts<'info> {
+    /// Mints the next cnft from the pool template to `leaf_owner`, the pool
+    /// signing as tree delegate and collection authority. Pools with a
+    /// collection mint into the first one.
+    pub fn mint(
+        &self,
+        pool: &mut Account<'info, HybridPoolConfig>,
+        collection_accounts: &CollectionAccounts<'info>,
+        leaf_owner: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let tree = self.tree()?;
+        if tree.merkle_tree.key() != pool.merkle_tree {
+            return Err(error!(anchor_lang::error::ErrorCode::ConstraintAddress));
+        }
+
+        let number = pool
+            .minted
+            .checked_add(1)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+        let collection = pool.collections.first().copied();
+        let metadata = pool
+            .mint_template
+            .metadata(pool.key(), pool.authority, number, collection);
+        pool.minted = number;
+
+        let pool_authority = pool.authority.key();
+        let pool_seeds = &[b"pool".as_ref(), pool_authority.as_ref(), &[pool.bump]];
+        let pool_signer = &[&pool_seeds[..]];
+        let pool_info = pool.to_account_info();
+
+        if let Some(collection) = collection {
+            let CollectionAccounts {
+                collection_mint: Some(collection_mint),
+                collection_metadata: Some(collection_metadata),
+                collection_edition: Some(collection_edition),
+                collection_authority_record,
+                bubblegum_signer: Some(bubblegum_signer),
+                token_metadata_program: Some(token_metadata_program),
+            } = collection_accounts
+            else {
+                return Err(error!(ErrorCode::MissingMintAccounts));
+            };
+            if collection_mint.key() != collection {
+                return Err(error!(ErrorCode::InvalidCollection));
+            }
+
+            MintToCollectionV1Cpi::new(
+                tree.bubblegum_program,
+                MintToCollectionV1CpiAccounts {
+                    tree_config: tree.tree_authority,
+                    leaf_owner,
+                    leaf_delegate: leaf_owner,
+                    merkle_tree: tree.merkle_tree,
+                    payer,
+                    tree_creator_or_delegate: &pool_info,
+                    collection_authority: &pool_info,
+                    collection_authority_record_pda: collection_authority_record
+                        .as_ref()
+                        .map(|record| record.as_ref()),
+                    collection_mint,
+                    collection_metadata,
+                    collection_edition,
+                    bubblegum_signer,
+                    log_wrapper: tree.log_wrapper,
+                    compression_program: tree.compression_program,
+                    token_metadata_program,
+                    system_program,
+                },
+                MintToCollectionV1InstructionArgs { metadata },
+            )
+            .invoke_signed(pool_signer)?;
+
+            return Ok(());
+        }
+
+        MintV1Cpi::new(
+            tree.bubblegum_program,
+            MintV1CpiAccounts {
+                tree_config: tree.tree_authority,
+                leaf_owner,
+                leaf_delegate: leaf_owner,
+                merkle_tree: tree.merkle_tree,
+                payer,
+                tree_creator_or_delegate: &pool_info,
+                log_wrapper: tree.log_wrapper,
+                compression_program: tree.compression_program,
+                system_program,
+            },
+            MintV1InstructionArgs { metadata },
+        )
+        .invoke_signed(pool_signer)?;
+
+        Ok(())
+    }
+}
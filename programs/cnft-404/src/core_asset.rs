@@ -0,0 +1,69 @@
+use crate::errors::ErrorCode;
+use crate::pool_asset::PoolAsset;
+use crate::state::*;
+use crate::validation::verify_core_admission;
+use anchor_lang::prelude::*;
+use mpl_core::accounts::{BaseAssetV1, BaseCollectionV1};
+use mpl_core::instructions::{TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs};
+use mpl_core::types::UpdateAuthority;
+
+/// Core asset being moved, with the collection it belongs to.
+#[derive(Accounts)]
+pub struct CoreAccounts<'info> {
+    #[account(mut)]
+    pub asset: Account<'info, BaseAssetV1>,
+    // core needs the collection to run its plugins when the asset has one
+    #[account(
+        mut,
+        constraint = asset.update_authority == UpdateAuthority::Collection(collection.key()) @ ErrorCode::InvalidCollection,
+    )]
+    pub collection: Option<Account<'info, BaseCollectionV1>>,
+    #[account(address = mpl_core::ID)]
+    ///CHECK: Core program
+    pub core_program: UncheckedAccount<'info>,
+}
+
+impl<'info> PoolAsset<'info> for CoreAccounts<'info> {
+    const KIND: AssetKind = AssetKind::Core;
+
+    fn asset_id(&self) -> Pubkey {
+        self.asset.key()
+    }
+
+    fn verify_admission(&self, pool: &HybridPoolConfig) -> Result<()> {
+        verify_core_admission(&self.asset, pool)
+    }
+
+    fn transfer(
+        &self,
+        owner: &AccountInfo<'info>,
+        new_owner: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let collection = self
+            .collection
+            .as_ref()
+            .map(|collection| collection.to_account_info());
+
+        TransferV1Cpi::new(
+            &self.core_program,
+            TransferV1CpiAccounts {
+                asset: &self.asset.to_account_info(),
+                collection: collection.as_ref(),
+                payer,
+                authority: Some(owner),
+                new_owner,
+                system_program: Some(system_program),
+                log_wrapper: None,
+            },
+            TransferV1InstructionArgs {
+                compression_proof: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+}
@@ -14,9 +14,153 @@ pub enum ErrorCode {
     #[msg("Invalid Collection cnft")]
     InvalidCollection,
 
+    #[msg("Collection not verified")]
+    UnverifiedCollection,
+
+    #[msg("Invalid Creator cnft")]
+    InvalidCreator,
+
+    #[msg("Invalid Creator Hash")]
+    InvalidCreatorHash,
+
+    #[msg("Too many creators")]
+    TooManyCreators,
+
+    #[msg("Asset not on the pool allowlist")]
+    AssetNotAllowlisted,
+
     #[msg("Collection Cannot be more than 3")]
     CannotBeMoreThanThree,
 
     #[msg("No cnfts in pool")]
     NoCnftsInPool,
+
+    #[msg("Math Overflow")]
+    MathOverflow,
+
+    #[msg("Payment accounts missing for the pool token")]
+    MissingPaymentAccounts,
+
+    #[msg("Not enough funds in the pool")]
+    InsufficientPoolFunds,
+
+    #[msg("Token not accepted by the pool")]
+    UnsupportedToken,
+
+    #[msg("Pool cannot accept more tokens")]
+    TooManyAcceptedTokens,
+
+    #[msg("Token already accepted by the pool")]
+    TokenAlreadyAccepted,
+
+    #[msg("Too many positions on the coupon")]
+    TooManyCouponPositions,
+
+    #[msg("Fee is too high")]
+    FeeTooHigh,
+
+    #[msg("Pool already has liquidity")]
+    PoolAlreadySeeded,
+
+    #[msg("Liquidity providers are not enabled")]
+    LiquidityProvidersDisabled,
+
+    #[msg("LP accounts missing")]
+    MissingLiquidityAccounts,
+
+    #[msg("Accepted tokens cannot be combined with liquidity providers")]
+    AcceptedTokensWithLiquidityProviders,
+
+    #[msg("Amount is too small")]
+    AmountTooSmall,
+
+    #[msg("Signer is not the pool authority")]
+    Unauthorized,
+
+    #[msg("Liquidity providers are enabled, deposit through add_liquidity")]
+    LiquidityProvidersEnabled,
+
+    #[msg("Metadata rules are invalid")]
+    InvalidMetadataRules,
+
+    #[msg("Cnft metadata does not match the pool rules")]
+    MetadataRuleMismatch,
+
+    #[msg("Pool burns cnfts instead of holding them")]
+    BurnModeEnabled,
+
+    #[msg("Burn mode cannot be combined with liquidity providers")]
+    BurnModeWithLiquidityProviders,
+
+    #[msg("Pool still holds cnfts")]
+    PoolHoldsCnfts,
+
+    #[msg("Pool is not the tree creator or delegate")]
+    PoolNotTreeDelegate,
+
+    #[msg("Mint template is invalid")]
+    InvalidMintTemplate,
+
+    #[msg("Mint accounts missing")]
+    MissingMintAccounts,
+
+    #[msg("Cnft store account missing")]
+    MissingNftStore,
+
+    #[msg("Claim coupon account missing")]
+    MissingCoupon,
+
+    #[msg("Bubblegum accounts missing")]
+    MissingBubblegumAccounts,
+
+    #[msg("Tree is too deep")]
+    TreeTooDeep,
+
+    #[msg("Pool position holds another kind of asset")]
+    WrongAssetKind,
+
+    #[msg("Pool has an admission rule Core assets cannot be checked against")]
+    UnsupportedCoreRule,
+
+    #[msg("Mint is not a non-fungible token")]
+    NotAnNft,
+
+    #[msg("Coupon has no re-rolls left")]
+    RerollLimitReached,
+
+    #[msg("Coupon has a draw waiting to be resolved")]
+    DrawPending,
+
+    #[msg("Coupon has no draw to resolve")]
+    NoPendingDraw,
+
+    #[msg("Slot hash for the draw is not available yet")]
+    DrawNotReady,
+
+    #[msg("Premium must be at least the base price")]
+    InvalidPremium,
+
+    #[msg("Pool does not sell chosen positions")]
+    PremiumPurchaseDisabled,
+
+    #[msg("Position is not in the pool")]
+    PositionNotInPool,
+
+    #[msg("Cnft is not the leaf held by the nft store")]
+    LeafNotInTree,
+
+    #[msg("Coupon belongs to another pool")]
+    CouponForAnotherPool,
+
+    #[msg("Position not on the coupon")]
+    PositionNotOnCoupon,
+
+    #[msg("Leaf accounts do not match the proof lengths")]
+    InvalidLeafAccounts,
+
+    #[msg("Cnft store does not belong to the position")]
+    InvalidNftStore,
+
+    #[msg("Payout is below the minimum")]
+    SlippageExceeded,
 }
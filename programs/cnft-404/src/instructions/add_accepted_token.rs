@@ -0,0 +1,41 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct AddAcceptedToken<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(
+        constraint = mint.decimals != 0,
+        constraint = !pool.accepts(&mint.key()) @ ErrorCode::TokenAlreadyAccepted,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn handler(ctx: Context<AddAcceptedToken>, price: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    if pool.has_liquidity_providers() {
+        return Err(error!(ErrorCode::AcceptedTokensWithLiquidityProviders));
+    }
+
+    if pool.accepted_tokens.len() >= MAX_ACCEPTED_TOKENS {
+        return Err(error!(ErrorCode::TooManyAcceptedTokens));
+    }
+
+    if price < 1000 {
+        return Err(error!(ErrorCode::PriceTooLow));
+    }
+
+    pool.accepted_tokens.push(AcceptedToken {
+        mint: ctx.accounts.mint.key(),
+        price,
+        deposited: 0,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::errors::ErrorCode;
+use crate::liquidity::mint_shares;
+use crate::math::shares_for_deposit;
+use crate::payment::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, constraint = payment.mint_key() == pool.token @ ErrorCode::UnsupportedToken)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(mut, address = pool.lp_mint)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = lp_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = lp_token_program,
+    )]
+    pub authority_lp_account: InterfaceAccount<'info, TokenAccount>,
+    pub payment: PaymentAccounts<'info>,
+    pub lp_token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    if !pool.has_liquidity_providers() {
+        return Err(error!(ErrorCode::LiquidityProvidersDisabled));
+    }
+
+    // shares are priced on what reaches the vault, net of any transfer fee
+    let received = ctx.accounts.payment.pay_in(
+        pool,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        amount,
+    )?;
+    let shares = shares_for_deposit(received, pool.value()?, ctx.accounts.lp_mint.supply)?;
+    if shares == 0 {
+        return Err(error!(ErrorCode::AmountTooSmall));
+    }
+
+    mint_shares(
+        pool,
+        &ctx.accounts.lp_mint,
+        ctx.accounts.authority_lp_account.to_account_info(),
+        &ctx.accounts.lp_token_program,
+        shares,
+    )?;
+
+    let pool = &mut ctx.accounts.pool;
+    let token = pool.token;
+    pool.credit(&token, received)?;
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use crate::bubblegum::*;
+use crate::errors::ErrorCode;
+use crate::math::{fee_amount, premium_price};
+use crate::payment::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::TransferInstructionArgs;
+
+#[derive(Accounts)]
+#[instruction(
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    pos: u8
+)]
+pub struct BuySpecificCnft<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = pool.premium_multiplier_bps != 0 @ ErrorCode::PremiumPurchaseDisabled,
+        constraint = pool.items.contains(&pos) @ ErrorCode::PositionNotInPool,
+        // positions committed to pending draws are not for sale
+        constraint = pool.undrawn_items() > 0 @ ErrorCode::NoCnftsInPool,
+    )]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account
+    (mut,
+        seeds = [b"cnft".as_ref(),pool.key().as_ref(),&pos.to_le_bytes()],
+        bump = nft_store.bump,
+        constraint = nft_store.kind == AssetKind::Compressed @ ErrorCode::WrongAssetKind,
+        close = authority)]
+    pub nft_store: Account<'info, NftStore>,
+    pub payment: PaymentAccounts<'info>,
+    pub bubblegum: BubblegumAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BuySpecificCnftArgs {
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+    pub pos: u8,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuySpecificCnft<'info>>,
+    args: BuySpecificCnftArgs,
+) -> Result<()> {
+    ctx.accounts
+        .bubblegum
+        .verify_stored_leaf(&ctx.accounts.nft_store, args.nonce)?;
+
+    let payment = &ctx.accounts.payment;
+    let payment_mint = payment.mint_key();
+    let pool = &mut ctx.accounts.pool;
+
+    let price = premium_price(pool.price_for(&payment_mint)?, pool.premium_multiplier_bps)?;
+    let amount = price
+        .checked_add(fee_amount(price, pool.fee_bps)?)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+    let received = payment.pay_in(
+        pool,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        amount,
+    )?;
+    pool.credit(&payment_mint, received)?;
+    pool.remove_item(args.pos)?;
+
+    let pos_bytes = args.pos.to_le_bytes();
+    let pool_key = pool.key();
+    let nft_store_seeds = &[
+        b"cnft".as_ref(),
+        pool_key.as_ref(),
+        &pos_bytes,
+        &[ctx.accounts.nft_store.bump],
+    ];
+    let nft_store_signer = &[&nft_store_seeds[..]];
+
+    ctx.accounts.bubblegum.transfer(
+        &ctx.accounts.nft_store.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        TransferInstructionArgs {
+            root: args.root,
+            data_hash: args.data_hash,
+            creator_hash: args.creator_hash,
+            nonce: args.nonce,
+            index: args.index,
+        },
+        ctx.remaining_accounts,
+        nft_store_signer,
+    )?;
+
+    Ok(())
+}
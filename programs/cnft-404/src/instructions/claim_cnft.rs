@@ -1,36 +1,36 @@
+use crate::bubblegum::*;
+use crate::errors::ErrorCode;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use mpl_bubblegum::instructions::{TransferCpi, TransferCpiAccounts, TransferInstructionArgs};
-use mpl_bubblegum::ID;
+use mpl_bubblegum::instructions::TransferInstructionArgs;
 
 #[derive(Accounts)]
+#[instruction(
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    pos: u8
+)]
 pub struct ClaimCnft<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
-    #[account(mut)]
     pub pool: Account<'info, HybridPoolConfig>,
     #[account(mut,
         seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
         bump = cnft_claim_coupon.bump,
-        close = authority)]
+        has_one = pool,
+        constraint = cnft_claim_coupon.positions.contains(&pos) @ ErrorCode::PositionNotOnCoupon)]
     pub cnft_claim_coupon: Account<'info, CnftClaimCoupon>,
     #[account
     (mut,
-        seeds = [b"cnft".as_ref(),pool.key().as_ref(),&cnft_claim_coupon.coupon.to_le_bytes()],
+        seeds = [b"cnft".as_ref(),pool.key().as_ref(),&pos.to_le_bytes()],
         bump = nft_store.bump,
+        constraint = nft_store.kind == AssetKind::Compressed @ ErrorCode::WrongAssetKind,
         close = authority)]
     pub nft_store: Account<'info, NftStore>,
-    /// CHECK: This account is neither written to nor read from.
-    pub tree_authority: UncheckedAccount<'info>,
-    ///CHECK: Checked in CPI
-    pub merkle_tree: UncheckedAccount<'info>,
-    ///CHECK: Checked in CPI
-    pub log_wrapper: UncheckedAccount<'info>,
-    #[account(address = ID  )]
-    ///CHECK: Checked in CPI
-    pub bubblegum_program: UncheckedAccount<'info>,
-    /// CHECK: This account is checked in the CPI
-    pub compression_program: UncheckedAccount<'info>,
+    pub bubblegum: BubblegumAccounts<'info>,
     pub system_program: Program<'info, System>,
 }
 
@@ -41,16 +41,19 @@ pub struct ClaimCnftArgs {
     pub creator_hash: [u8; 32],
     pub nonce: u64,
     pub index: u32,
+    /// Position on the coupon to claim.
+    pub pos: u8,
 }
 
-pub fn handler<'info>(
+pub(crate) fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, ClaimCnft<'info>>,
     args: ClaimCnftArgs,
 ) -> Result<()> {
-    let pool = &mut ctx.accounts.pool;
-    let coupon = ctx.accounts.cnft_claim_coupon.coupon as usize;
-    let coupon_bytes = ctx.accounts.cnft_claim_coupon.coupon.to_le_bytes();
-    pool.items.remove(coupon);
+    ctx.accounts
+        .bubblegum
+        .verify_stored_leaf(&ctx.accounts.nft_store, args.nonce)?;
+
+    let coupon_bytes = args.pos.to_le_bytes();
 
     let pool_key = ctx.accounts.pool.key();
     let nft_store_seeds = &[
@@ -61,18 +64,10 @@ pub fn handler<'info>(
     ];
     let nft_store_signer = &[&nft_store_seeds[..]];
 
-    TransferCpi::new(
-        &ctx.accounts.bubblegum_program,
-        TransferCpiAccounts {
-            tree_config: &ctx.accounts.tree_authority.to_account_info(),
-            leaf_owner: (&ctx.accounts.nft_store.to_account_info(), true),
-            leaf_delegate: (&ctx.accounts.nft_store.to_account_info(), false),
-            new_leaf_owner: &ctx.accounts.authority.to_account_info(),
-            merkle_tree: &ctx.accounts.merkle_tree.to_account_info(),
-            log_wrapper: &ctx.accounts.log_wrapper.to_account_info(),
-            compression_program: &ctx.accounts.compression_program.to_account_info(),
-            system_program: &ctx.accounts.system_program.to_account_info(),
-        },
+    ctx.accounts.bubblegum.transfer(
+        &ctx.accounts.nft_store.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
         TransferInstructionArgs {
             root: args.root,
             data_hash: args.data_hash,
@@ -80,15 +75,13 @@ pub fn handler<'info>(
             nonce: args.nonce,
             index: args.index,
         },
-    )
-    .invoke_signed_with_remaining_accounts(
+        ctx.remaining_accounts,
         nft_store_signer,
-        ctx.remaining_accounts
-            .iter()
-            .map(|account| (account, false, false))
-            .collect::<Vec<_>>()
-            .as_slice(),
     )?;
 
-    Ok(())
+    CnftClaimCoupon::claim(
+        &mut ctx.accounts.cnft_claim_coupon,
+        args.pos,
+        ctx.accounts.authority.to_account_info(),
+    )
 }
@@ -0,0 +1,83 @@
+use crate::batch::{split_leaf_accounts, verify_nft_store_address};
+use crate::bubblegum::*;
+use crate::errors::ErrorCode;
+use crate::instructions::ClaimCnftArgs;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::TransferInstructionArgs;
+
+#[derive(Accounts)]
+pub struct ClaimCnfts<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(mut,
+        seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
+        bump = cnft_claim_coupon.bump,
+        has_one = pool)]
+    pub cnft_claim_coupon: Account<'info, CnftClaimCoupon>,
+    pub bubblegum: BubblegumAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claims several coupon positions from one tree. The remaining accounts
+/// hold, for each claim, its nft store followed by `proof_lengths[i]` proof
+/// nodes. Positions held in another tree fail with `LeafNotInTree` and are
+/// claimed in a separate call.
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimCnfts<'info>>,
+    claims: Vec<ClaimCnftArgs>,
+    proof_lengths: Vec<u8>,
+) -> Result<()> {
+    if claims.len() != proof_lengths.len() {
+        return Err(error!(ErrorCode::InvalidLeafAccounts));
+    }
+    let leaves = split_leaf_accounts(ctx.remaining_accounts, &proof_lengths)?;
+
+    let authority = ctx.accounts.authority.to_account_info();
+    let pool_key = ctx.accounts.pool.key();
+    for (claim, leaf) in claims.iter().zip(leaves) {
+        let coupon = &mut ctx.accounts.cnft_claim_coupon;
+        if !coupon.positions.contains(&claim.pos) {
+            return Err(error!(ErrorCode::PositionNotOnCoupon));
+        }
+
+        let nft_store = Account::<NftStore>::try_from(leaf.nft_store)?;
+        verify_nft_store_address(leaf.nft_store.key, &pool_key, claim.pos, nft_store.bump)?;
+        if nft_store.kind != AssetKind::Compressed {
+            return Err(error!(ErrorCode::WrongAssetKind));
+        }
+        ctx.accounts
+            .bubblegum
+            .verify_stored_leaf(&nft_store, claim.nonce)?;
+
+        let pos_bytes = claim.pos.to_le_bytes();
+        let nft_store_seeds = &[
+            b"cnft".as_ref(),
+            pool_key.as_ref(),
+            &pos_bytes,
+            &[nft_store.bump],
+        ];
+        let nft_store_signer = &[&nft_store_seeds[..]];
+
+        ctx.accounts.bubblegum.transfer(
+            leaf.nft_store,
+            &authority,
+            &ctx.accounts.system_program.to_account_info(),
+            TransferInstructionArgs {
+                root: claim.root,
+                data_hash: claim.data_hash,
+                creator_hash: claim.creator_hash,
+                nonce: claim.nonce,
+                index: claim.index,
+            },
+            leaf.proof,
+            nft_store_signer,
+        )?;
+        nft_store.close(authority.clone())?;
+
+        CnftClaimCoupon::claim(coupon, claim.pos, authority.clone())?;
+    }
+
+    Ok(())
+}
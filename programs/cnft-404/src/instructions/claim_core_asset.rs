@@ -0,0 +1,46 @@
+use crate::core_asset::*;
+use crate::errors::ErrorCode;
+use crate::pool_asset::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(pos: u8)]
+pub struct ClaimCoreAsset<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(mut,
+        seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
+        bump = cnft_claim_coupon.bump,
+        has_one = pool,
+        constraint = cnft_claim_coupon.positions.contains(&pos) @ ErrorCode::PositionNotOnCoupon)]
+    pub cnft_claim_coupon: Account<'info, CnftClaimCoupon>,
+    #[account
+    (mut,
+        seeds = [b"cnft".as_ref(),pool.key().as_ref(),&pos.to_le_bytes()],
+        bump = nft_store.bump,
+        constraint = nft_store.kind == AssetKind::Core @ ErrorCode::WrongAssetKind,
+        constraint = nft_store.asset_id == core.asset.key() @ ErrorCode::WrongAssetKind,
+        close = authority)]
+    pub nft_store: Account<'info, NftStore>,
+    pub core: CoreAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<ClaimCoreAsset>, pos: u8) -> Result<()> {
+    release_asset(
+        &ctx.accounts.core,
+        ctx.accounts.pool.key(),
+        pos,
+        &ctx.accounts.nft_store,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+    )?;
+
+    CnftClaimCoupon::claim(
+        &mut ctx.accounts.cnft_claim_coupon,
+        pos,
+        ctx.accounts.authority.to_account_info(),
+    )
+}
@@ -0,0 +1,46 @@
+use crate::errors::ErrorCode;
+use crate::legacy_asset::*;
+use crate::pool_asset::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(pos: u8)]
+pub struct ClaimLegacyNft<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(mut,
+        seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
+        bump = cnft_claim_coupon.bump,
+        has_one = pool,
+        constraint = cnft_claim_coupon.positions.contains(&pos) @ ErrorCode::PositionNotOnCoupon)]
+    pub cnft_claim_coupon: Account<'info, CnftClaimCoupon>,
+    #[account
+    (mut,
+        seeds = [b"cnft".as_ref(),pool.key().as_ref(),&pos.to_le_bytes()],
+        bump = nft_store.bump,
+        constraint = nft_store.kind == AssetKind::Legacy @ ErrorCode::WrongAssetKind,
+        constraint = nft_store.asset_id == legacy.mint.key() @ ErrorCode::WrongAssetKind,
+        close = authority)]
+    pub nft_store: Account<'info, NftStore>,
+    pub legacy: LegacyAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<ClaimLegacyNft>, pos: u8) -> Result<()> {
+    release_asset(
+        &ctx.accounts.legacy,
+        ctx.accounts.pool.key(),
+        pos,
+        &ctx.accounts.nft_store,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+    )?;
+
+    CnftClaimCoupon::claim(
+        &mut ctx.accounts.cnft_claim_coupon,
+        pos,
+        ctx.accounts.authority.to_account_info(),
+    )
+}
@@ -0,0 +1,42 @@
+use crate::bubblegum::*;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreatePoolTree<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    pub bubblegum: BubblegumAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates a private Bubblegum tree with the pool as tree creator, so the
+/// pool can mint into it.
+pub(crate) fn handler(
+    ctx: Context<CreatePoolTree>,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Result<()> {
+    if max_depth > MAX_TREE_DEPTH {
+        return Err(error!(ErrorCode::TreeTooDeep));
+    }
+
+    ctx.accounts.bubblegum.create_tree(
+        &ctx.accounts.pool,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        max_depth,
+        max_buffer_size,
+    )?;
+
+    let merkle_tree = ctx.accounts.bubblegum.merkle_tree_key()?;
+    let pool = &mut ctx.accounts.pool;
+    pool.merkle_tree = merkle_tree;
+    pool.tree_max_depth = max_depth;
+    pool.tree_max_buffer_size = max_buffer_size;
+
+    Ok(())
+}
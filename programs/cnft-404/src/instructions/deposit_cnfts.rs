@@ -0,0 +1,78 @@
+use crate::batch::{init_nft_store, split_leaf_accounts};
+use crate::bubblegum::*;
+use crate::errors::ErrorCode;
+use crate::instructions::DepositCnftArgs;
+use crate::liquidity::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DepositCnfts<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // anyone can provide cnfts once liquidity providers are enabled
+    #[account(
+        mut,
+        constraint = pool.authority == authority.key() || pool.has_liquidity_providers() @ ErrorCode::Unauthorized,
+        constraint = !pool.burn_mode @ ErrorCode::BurnModeEnabled,
+    )]
+    pub pool: Account<'info, HybridPoolConfig>,
+    pub bubblegum: BubblegumAccounts<'info>,
+    pub lp: LpAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposits several cnfts from one tree. The remaining accounts hold, for
+/// each leaf, its nft store followed by `proof_lengths[i]` proof nodes. The
+/// stores are created here and may already hold lamports.
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositCnfts<'info>>,
+    leaves: Vec<DepositCnftArgs>,
+    proof_lengths: Vec<u8>,
+) -> Result<()> {
+    if leaves.len() != proof_lengths.len() {
+        return Err(error!(ErrorCode::InvalidLeafAccounts));
+    }
+    let leaf_accounts = split_leaf_accounts(ctx.remaining_accounts, &proof_lengths)?;
+
+    // shares are minted at once, valued before any of the cnfts joins
+    if ctx.accounts.pool.has_liquidity_providers() {
+        ctx.accounts.lp.mint_item_shares(
+            &ctx.accounts.pool,
+            leaves.len(),
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+        )?;
+    }
+
+    let authority = ctx.accounts.authority.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let pool_key = ctx.accounts.pool.key();
+    for (leaf, accounts) in leaves.into_iter().zip(leaf_accounts) {
+        let pos = leaf.pos;
+        let leaf = leaf.into_leaf(accounts.proof)?;
+        let asset_id = ctx.accounts.bubblegum.take_cnft(
+            &ctx.accounts.pool,
+            &leaf,
+            &authority,
+            accounts.nft_store,
+            &system_program,
+        )?;
+        init_nft_store(
+            accounts.nft_store,
+            &authority,
+            &system_program,
+            &pool_key,
+            pos,
+            |bump| NftStore {
+                asset_id,
+                bump,
+                kind: AssetKind::Compressed,
+            },
+        )?;
+
+        ctx.accounts.pool.add_item(pos);
+    }
+
+    Ok(())
+}
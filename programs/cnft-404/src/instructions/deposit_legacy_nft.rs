@@ -0,0 +1,60 @@
+use crate::errors::ErrorCode;
+use crate::legacy_asset::*;
+use crate::liquidity::*;
+use crate::pool_asset::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(pos: u8)]
+pub struct DepositLegacyNft<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // anyone can provide nfts once liquidity providers are enabled
+    #[account(
+        mut,
+        constraint = pool.authority == authority.key() || pool.has_liquidity_providers() @ ErrorCode::Unauthorized,
+        constraint = !pool.burn_mode @ ErrorCode::BurnModeEnabled,
+    )]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(
+        init,
+        seeds = [b"cnft".as_ref(),pool.key().as_ref(),&pos.to_le_bytes()],
+        bump,
+        space = NftStore::LEN,
+        payer = authority,
+    )]
+    pub nft_store: Account<'info, NftStore>,
+    pub legacy: LegacyAccounts<'info>,
+    pub lp: LpAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositLegacyNft<'info>>,
+    pos: u8,
+    asset_proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    store_asset(
+        &ctx.accounts.legacy,
+        &ctx.accounts.pool,
+        &mut ctx.accounts.nft_store,
+        ctx.bumps.nft_store,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        &asset_proof,
+    )?;
+
+    if ctx.accounts.pool.has_liquidity_providers() {
+        ctx.accounts.lp.mint_item_shares(
+            &ctx.accounts.pool,
+            1,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+        )?;
+    }
+
+    ctx.accounts.pool.add_item(pos);
+
+    Ok(())
+}
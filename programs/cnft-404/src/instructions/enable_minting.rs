@@ -0,0 +1,64 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use mpl_bubblegum::accounts::TreeConfig;
+
+#[derive(Accounts)]
+pub struct EnableMinting<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = mpl_bubblegum::ID
+    )]
+    /// CHECK: Deserialized as a Bubblegum tree config below
+    pub tree_authority: UncheckedAccount<'info>,
+    ///CHECK: Only its key is recorded
+    pub merkle_tree: UncheckedAccount<'info>,
+}
+
+/// Lets the pool mint cnfts from `template` into the tree. In `burn_mode`
+/// the pool never holds cnfts, otherwise it mints whenever it runs out.
+pub(crate) fn handler(
+    ctx: Context<EnableMinting>,
+    template: MintTemplate,
+    burn_mode: bool,
+) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut ctx.accounts.pool;
+
+    if !template.is_valid() {
+        return Err(error!(ErrorCode::InvalidMintTemplate));
+    }
+
+    if burn_mode {
+        // held cnfts could never leave a pool that only mints
+        if !pool.items.is_empty() {
+            return Err(error!(ErrorCode::PoolHoldsCnfts));
+        }
+
+        if pool.has_liquidity_providers() {
+            return Err(error!(ErrorCode::BurnModeWithLiquidityProviders));
+        }
+    }
+
+    let tree_config = TreeConfig::try_from(&ctx.accounts.tree_authority.to_account_info())
+        .map_err(|_| error!(ErrorCode::PoolNotTreeDelegate))?;
+    if tree_config.tree_creator != pool_key && tree_config.tree_delegate != pool_key {
+        return Err(error!(ErrorCode::PoolNotTreeDelegate));
+    }
+
+    let merkle_tree = ctx.accounts.merkle_tree.key();
+    if pool.merkle_tree != merkle_tree {
+        pool.merkle_tree = merkle_tree;
+        pool.tree_max_depth = 0;
+        pool.tree_max_buffer_size = 0;
+    }
+    pool.burn_mode = burn_mode;
+    pool.admit_own_cnfts(pool_key);
+    pool.mint_template = template;
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct InitLiquidity<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(constraint = pool.token == mint.key())]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"lp_mint".as_ref(),pool.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = pool,
+        mint::token_program = lp_token_program,
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    pub lp_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<InitLiquidity>, fee_bps: u16) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    if fee_bps > MAX_FEE_BPS {
+        return Err(error!(ErrorCode::FeeTooHigh));
+    }
+
+    // shares are only fair if every unit of value was deposited through them
+    if !pool.items.is_empty() || pool.reserve != 0 || pool.initiated {
+        return Err(error!(ErrorCode::PoolAlreadySeeded));
+    }
+
+    if !pool.accepted_tokens.is_empty() {
+        return Err(error!(ErrorCode::AcceptedTokensWithLiquidityProviders));
+    }
+
+    if pool.burn_mode {
+        return Err(error!(ErrorCode::BurnModeWithLiquidityProviders));
+    }
+
+    pool.lp_mint = ctx.accounts.lp_mint.key();
+    pool.fee_bps = fee_bps;
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use crate::errors::ErrorCode;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
+use anchor_spl::token_interface::Mint;
 
 #[derive(Accounts)]
 pub struct InitPool<'info> {
@@ -18,11 +18,11 @@ pub struct InitPool<'info> {
     #[account(
         constraint = mint.decimals != 0,
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<InitPool>, collections: Vec<Pubkey>, price: u64) -> Result<()> {
+pub(crate) fn handler(ctx: Context<InitPool>, collections: Vec<Pubkey>, price: u64) -> Result<()> {
     if collections.len() > MAX_COLLECTIONS {
         return Err(error!(ErrorCode::CannotBeMoreThanThree));
     }
@@ -39,6 +39,28 @@ pub fn handler(ctx: Context<InitPool>, collections: Vec<Pubkey>, price: u64) ->
         bump: ctx.bumps.pool,
         initiated: false,
         items: vec![],
+        accepted_tokens: vec![],
+        reserve: 0,
+        lp_mint: Pubkey::default(),
+        fee_bps: 0,
+        deposited: 0,
+        creators: vec![],
+        asset_allowlist_root: [0; 32],
+        metadata_rules: MetadataRules::default(),
+        burn_mode: false,
+        merkle_tree: Pubkey::default(),
+        mint_template: MintTemplate::default(),
+        minted: 0,
+        tree_max_depth: 0,
+        tree_max_buffer_size: 0,
+        reroll_fee_bps: 0,
+        max_rerolls: None,
+        cnft_swap_fee_bps: 0,
+        premium_multiplier_bps: 0,
+        item_tiers: vec![],
+        tier_weights: [0; TIER_COUNT],
+        tier_counts: [0; TIER_COUNT],
+        pending_draws: 0,
     });
 
     Ok(())
@@ -1,14 +1,68 @@
+pub mod add_accepted_token;
+pub mod add_liquidity;
+pub mod buy_specific_cnft;
 pub mod claim_cnft;
+pub mod claim_cnfts;
+pub mod claim_core_asset;
+pub mod claim_legacy_nft;
+pub mod create_pool_tree;
 #[warn(ambiguous_glob_reexports)]
 pub mod deposit_cnft;
+pub mod deposit_cnfts;
+pub mod deposit_core_asset;
+pub mod deposit_legacy_nft;
 pub mod deposit_token;
+pub mod enable_minting;
+pub mod init_liquidity;
 pub mod init_pool;
+pub mod remove_liquidity;
+pub mod reroll;
+pub mod resolve_coupon;
+pub mod set_asset_allowlist_root;
+pub mod set_cnft_swap_fee;
+pub mod set_creators;
+pub mod set_item_tier;
+pub mod set_metadata_rules;
+pub mod set_premium_multiplier;
+pub mod set_reroll_config;
+pub mod set_tier_weights;
+pub mod swap_cnft_to_cnft;
 pub mod swap_cnft_to_token;
+pub mod swap_cnfts_to_token;
+pub mod swap_core_asset_to_token;
+pub mod swap_legacy_nft_to_token;
 pub mod swap_token_to_cnft;
 
+pub use add_accepted_token::*;
+pub use add_liquidity::*;
+pub use buy_specific_cnft::*;
 pub use claim_cnft::*;
+pub use claim_cnfts::*;
+pub use claim_core_asset::*;
+pub use claim_legacy_nft::*;
+pub use create_pool_tree::*;
 pub use deposit_cnft::*;
+pub use deposit_cnfts::*;
+pub use deposit_core_asset::*;
+pub use deposit_legacy_nft::*;
 pub use deposit_token::*;
+pub use enable_minting::*;
+pub use init_liquidity::*;
 pub use init_pool::*;
+pub use remove_liquidity::*;
+pub use reroll::*;
+pub use resolve_coupon::*;
+pub use set_asset_allowlist_root::*;
+pub use set_cnft_swap_fee::*;
+pub use set_creators::*;
+pub use set_item_tier::*;
+pub use set_metadata_rules::*;
+pub use set_premium_multiplier::*;
+pub use set_reroll_config::*;
+pub use set_tier_weights::*;
+pub use swap_cnft_to_cnft::*;
 pub use swap_cnft_to_token::*;
+pub use swap_cnfts_to_token::*;
+pub use swap_core_asset_to_token::*;
+pub use swap_legacy_nft_to_token::*;
 pub use swap_token_to_cnft::*;
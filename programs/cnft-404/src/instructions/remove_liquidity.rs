@@ -0,0 +1,100 @@
+use crate::errors::ErrorCode;
+use crate::math::{pro_rata, total_price};
+use crate::payment::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, constraint = payment.mint_key() == pool.token @ ErrorCode::UnsupportedToken)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    pub payment: PaymentAccounts<'info>,
+    #[account(mut, address = pool.lp_mint)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = lp_token_program,
+    )]
+    pub authority_lp_account: InterfaceAccount<'info, TokenAccount>,
+    /// Receives the cnfts drawn for the withdrawn shares.
+    #[account(
+        init_if_needed,
+        space = CnftClaimCoupon::LEN,
+        payer = authority,
+        seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
+        bump,
+    )]
+    pub cnft_claim_coupon: Option<Account<'info, CnftClaimCoupon>>,
+    pub lp_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<RemoveLiquidity>, shares: u64) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    if !pool.has_liquidity_providers() {
+        return Err(error!(ErrorCode::LiquidityProvidersDisabled));
+    }
+    if shares == 0 {
+        return Err(error!(ErrorCode::AmountTooSmall));
+    }
+
+    // whole cnfts are drawn at random, the fraction of a cnft is paid in tokens
+    let supply = ctx.accounts.lp_mint.supply;
+    let items = pool.items.len() as u64;
+    let cnft_count = pro_rata(items, shares, supply)?;
+    let cnft_value = pro_rata(total_price(pool.price, pool.items.len())?, shares, supply)?;
+    let cnft_remainder = cnft_value
+        .checked_sub(total_price(pool.price, cnft_count as usize)?)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+    let token_amount = pro_rata(pool.reserve, shares, supply)?
+        .checked_add(cnft_remainder)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))?
+        .min(pool.reserve);
+
+    let burn_accounts = Burn {
+        mint: ctx.accounts.lp_mint.to_account_info(),
+        from: ctx.accounts.authority_lp_account.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+    };
+    let burn_context = CpiContext::new(
+        ctx.accounts.lp_token_program.to_account_info(),
+        burn_accounts,
+    );
+    token_interface::burn(burn_context, shares)?;
+
+    if token_amount > 0 {
+        ctx.accounts.payment.pay_out(
+            pool,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            token_amount,
+        )?;
+    }
+
+    let pool_key = pool.key();
+    let pool = &mut ctx.accounts.pool;
+    let token = pool.token;
+    pool.debit(&token, token_amount)?;
+
+    if cnft_count > 0 {
+        let (Some(cnft_claim_coupon), Some(coupon_bump)) = (
+            &mut ctx.accounts.cnft_claim_coupon,
+            ctx.bumps.cnft_claim_coupon,
+        ) else {
+            return Err(error!(ErrorCode::MissingLiquidityAccounts));
+        };
+        // drawn by `resolve_coupon` from the hash of a later slot
+        let cnft_count =
+            u8::try_from(cnft_count).map_err(|_| error!(ErrorCode::TooManyCouponPositions))?;
+        pool.commit_draws(cnft_count)?;
+        cnft_claim_coupon.commit_draws(pool_key, coupon_bump, cnft_count, Clock::get()?.slot)?;
+    }
+
+    Ok(())
+}
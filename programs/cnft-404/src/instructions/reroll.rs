@@ -0,0 +1,50 @@
+use crate::errors::ErrorCode;
+use crate::math::fee_amount;
+use crate::payment::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(pos: u8)]
+pub struct Reroll<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(mut,
+        seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
+        bump = cnft_claim_coupon.bump,
+        has_one = pool,
+        constraint = cnft_claim_coupon.positions.contains(&pos) @ ErrorCode::PositionNotOnCoupon)]
+    pub cnft_claim_coupon: Account<'info, CnftClaimCoupon>,
+    pub payment: PaymentAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<Reroll>, pos: u8) -> Result<()> {
+    let payment = &ctx.accounts.payment;
+    let payment_mint = payment.mint_key();
+    let pool = &mut ctx.accounts.pool;
+
+    let fee = fee_amount(pool.price_for(&payment_mint)?, pool.reroll_fee_bps)?;
+    if fee > 0 {
+        let received = payment.pay_in(
+            pool,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            fee,
+        )?;
+        pool.credit(&payment_mint, received)?;
+    }
+
+    // the new position is drawn by `resolve_coupon` once a later slot hash
+    // exists, so the outcome is unknown while the fee can still be withheld
+    let slot = Clock::get()?.slot;
+    ctx.accounts
+        .cnft_claim_coupon
+        .reroll(pos, slot, pool.max_rerolls)?;
+    pool.return_item(pos);
+    pool.commit_draws(1)?;
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::errors::ErrorCode;
+use crate::random::{resolve_positions, slot_hash_after, SlotHashAfter};
+use crate::state::*;
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+#[derive(Accounts)]
+pub struct ResolveCoupon<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(mut,
+        seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
+        bump = cnft_claim_coupon.bump,
+        has_one = pool)]
+    pub cnft_claim_coupon: Account<'info, CnftClaimCoupon>,
+    #[account(address = sysvar::slot_hashes::id())]
+    /// CHECK: This is a sysvar
+    pub recent_slot_hashes: UncheckedAccount<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<ResolveCoupon>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let coupon = &mut ctx.accounts.cnft_claim_coupon;
+    let Some(slot) = coupon.pending_slot else {
+        return Err(error!(ErrorCode::NoPendingDraw));
+    };
+    let swapped_in = coupon.swapped_in.take();
+
+    let slot_hashes = ctx.accounts.recent_slot_hashes.try_borrow_data()?;
+    match slot_hash_after(&slot_hashes, slot) {
+        SlotHashAfter::Pending => return Err(error!(ErrorCode::DrawNotReady)),
+        SlotHashAfter::Ready(slot_hash) => {
+            let authority = ctx.accounts.authority.key();
+            let positions = resolve_positions(
+                pool,
+                coupon.pending_draws,
+                &slot_hash,
+                &[authority.as_ref(), &[coupon.rerolls]],
+            )?;
+            pool.release_draws(coupon.pending_draws);
+            coupon.resolve(&positions);
+        }
+        // the payment or old position is already given up, so waiting out a
+        // bad draw forfeits the draws instead of keeping them
+        SlotHashAfter::Expired => {
+            pool.release_draws(coupon.forfeit_draws());
+            if coupon.is_spent() {
+                coupon.close(ctx.accounts.authority.to_account_info())?;
+            }
+        }
+    }
+
+    // added after the draw so a swap never hands back the deposited cnft
+    if let Some(pos) = swapped_in {
+        pool.add_item(pos);
+    }
+
+    Ok(())
+}
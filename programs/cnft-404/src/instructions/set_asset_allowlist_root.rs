@@ -0,0 +1,16 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetAssetAllowlistRoot<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+}
+
+/// An all zero `root` removes the allowlist.
+pub(crate) fn handler(ctx: Context<SetAssetAllowlistRoot>, root: [u8; 32]) -> Result<()> {
+    ctx.accounts.pool.asset_allowlist_root = root;
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetCnftSwapFee<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+}
+
+pub(crate) fn handler(ctx: Context<SetCnftSwapFee>, fee_bps: u16) -> Result<()> {
+    if fee_bps > MAX_FEE_BPS {
+        return Err(error!(ErrorCode::FeeTooHigh));
+    }
+
+    ctx.accounts.pool.cnft_swap_fee_bps = fee_bps;
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetCreators<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+}
+
+pub(crate) fn handler(ctx: Context<SetCreators>, creators: Vec<Pubkey>) -> Result<()> {
+    if creators.len() > MAX_CREATORS {
+        return Err(error!(ErrorCode::TooManyCreators));
+    }
+
+    ctx.accounts.pool.creators = creators;
+
+    Ok(())
+}
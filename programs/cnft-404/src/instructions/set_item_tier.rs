@@ -0,0 +1,13 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetItemTier<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+}
+
+pub(crate) fn handler(ctx: Context<SetItemTier>, pos: u8, tier: Tier) -> Result<()> {
+    ctx.accounts.pool.set_tier(pos, tier)
+}
@@ -0,0 +1,20 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetMetadataRules<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+}
+
+pub(crate) fn handler(ctx: Context<SetMetadataRules>, rules: MetadataRules) -> Result<()> {
+    if !rules.is_valid() {
+        return Err(error!(ErrorCode::InvalidMetadataRules));
+    }
+
+    ctx.accounts.pool.metadata_rules = rules;
+
+    Ok(())
+}
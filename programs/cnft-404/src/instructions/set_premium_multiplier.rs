@@ -0,0 +1,21 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPremiumMultiplier<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+}
+
+pub(crate) fn handler(ctx: Context<SetPremiumMultiplier>, multiplier_bps: u32) -> Result<()> {
+    // 0 turns chosen purchases off, anything else must not undercut random ones
+    if multiplier_bps != 0 && multiplier_bps < BASE_MULTIPLIER_BPS {
+        return Err(error!(ErrorCode::InvalidPremium));
+    }
+
+    ctx.accounts.pool.premium_multiplier_bps = multiplier_bps;
+
+    Ok(())
+}
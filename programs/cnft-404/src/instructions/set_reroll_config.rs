@@ -0,0 +1,26 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRerollConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<SetRerollConfig>,
+    reroll_fee_bps: u16,
+    max_rerolls: Option<u8>,
+) -> Result<()> {
+    if reroll_fee_bps > MAX_FEE_BPS {
+        return Err(error!(ErrorCode::FeeTooHigh));
+    }
+
+    let pool = &mut ctx.accounts.pool;
+    pool.reroll_fee_bps = reroll_fee_bps;
+    pool.max_rerolls = max_rerolls;
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetTierWeights<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one = authority)]
+    pub pool: Account<'info, HybridPoolConfig>,
+}
+
+pub(crate) fn handler(ctx: Context<SetTierWeights>, weights: [u16; TIER_COUNT]) -> Result<()> {
+    ctx.accounts.pool.tier_weights = weights;
+
+    Ok(())
+}
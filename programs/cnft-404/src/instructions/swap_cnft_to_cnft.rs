@@ -0,0 +1,90 @@
+use crate::bubblegum::*;
+use crate::errors::ErrorCode;
+use crate::instructions::SwapCnftArgs;
+use crate::math::fee_amount;
+use crate::payment::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+    pos: u8
+)]
+pub struct SwapCnftToCnft<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = !pool.burn_mode @ ErrorCode::BurnModeEnabled,
+    )]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(
+        init,
+        seeds = [b"cnft".as_ref(),pool.key().as_ref(),&pos.to_le_bytes()],
+        bump,
+        space = NftStore::LEN,
+        payer = authority,
+    )]
+    pub nft_store: Account<'info, NftStore>,
+    #[account(
+        init_if_needed,
+        space = CnftClaimCoupon::LEN,
+        payer = authority,
+        seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
+        bump,
+    )]
+    pub cnft_claim_coupon: Account<'info, CnftClaimCoupon>,
+    // only needed when the pool charges a swap fee
+    pub payment: PaymentAccounts<'info>,
+    pub bubblegum: BubblegumAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapCnftToCnft<'info>>,
+    args: SwapCnftArgs,
+) -> Result<()> {
+    let pos = args.pos;
+    let leaf = args.into_leaf(ctx.remaining_accounts)?;
+    ctx.accounts.bubblegum.store_cnft(
+        &ctx.accounts.pool,
+        &leaf,
+        &ctx.accounts.authority,
+        &mut ctx.accounts.nft_store,
+        ctx.bumps.nft_store,
+        &ctx.accounts.system_program,
+    )?;
+
+    let payment = &ctx.accounts.payment;
+    let pool = &mut ctx.accounts.pool;
+    if pool.cnft_swap_fee_bps > 0 {
+        let payment_mint = payment.mint_key();
+        let fee = fee_amount(pool.price_for(&payment_mint)?, pool.cnft_swap_fee_bps)?;
+        let received = payment.pay_in(
+            pool,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            fee,
+        )?;
+        pool.credit(&payment_mint, received)?;
+    }
+
+    // drawn by `resolve_coupon` from the hash of a later slot, which adds the
+    // deposited cnft to the pool afterwards so the user gets a different one
+    pool.commit_draws(1)?;
+    let coupon = &mut ctx.accounts.cnft_claim_coupon;
+    coupon.commit_draws(
+        pool.key(),
+        ctx.bumps.cnft_claim_coupon,
+        1,
+        Clock::get()?.slot,
+    )?;
+    coupon.swapped_in = Some(pos);
+
+    Ok(())
+}
@@ -1,22 +1,11 @@
+use crate::bubblegum::*;
 use crate::errors::ErrorCode;
+use crate::payment::*;
 use crate::state::*;
-use crate::AnchorMetadataArgs;
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token;
-use anchor_spl::token::Mint;
-use anchor_spl::token::Token;
-use anchor_spl::token::TokenAccount;
-use anchor_spl::token::Transfer;
-use mpl_bubblegum::hash::hash_metadata;
-use mpl_bubblegum::instructions::TransferCpi;
-use mpl_bubblegum::instructions::TransferCpiAccounts;
-use mpl_bubblegum::instructions::TransferInstructionArgs;
+use mpl_bubblegum::instructions::BurnInstructionArgs;
 use mpl_bubblegum::types::MetadataArgs;
 
-use mpl_bubblegum::utils::get_asset_id;
-use mpl_bubblegum::ID;
-
 #[derive(Accounts)]
 #[instruction(
     root: [u8; 32],
@@ -31,44 +20,19 @@ pub struct SwapCnft<'info> {
     pub authority: Signer<'info>,
     #[account(mut)]
     pub pool: Account<'info, HybridPoolConfig>,
+    // left out in burn mode, the cnft is burned instead of stored
     #[account(
         init,
         seeds = [b"cnft".as_ref(),pool.key().as_ref(),&pos.to_le_bytes()],
         bump,
-        space = 33,
+        space = NftStore::LEN,
         payer = authority,
+        constraint = !pool.burn_mode @ ErrorCode::BurnModeEnabled,
     )]
-    pub nft_store: Account<'info, NftStore>,
-    #[account(constraint = pool.token == mint.key())]
-    pub mint: Account<'info, Mint>,
-    #[
-        account(
-            mut,
-            associated_token::mint = mint,
-            associated_token::authority = pool,
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-    #[account(
-        init_if_needed,
-        payer = authority,
-        associated_token::authority =  authority,
-        associated_token::mint = mint,
-    )]
-    pub authority_token_account: Account<'info, TokenAccount>,
-    ///CHECK: Checked in CPI
-    pub tree_authority: UncheckedAccount<'info>,
-    ///CHECK: Checked in CPI
-    pub merkle_tree: UncheckedAccount<'info>,
-    ///CHECK: Checked in CPI
-    pub log_wrapper: UncheckedAccount<'info>,
-    #[account(address = ID  )]
-    ///CHECK: Checked in CPI
-    pub bubblegum_program: UncheckedAccount<'info>,
-    /// CHECK: This account is checked in the CPI
-    pub compression_program: UncheckedAccount<'info>,
+    pub nft_store: Option<Account<'info, NftStore>>,
+    pub payment: PaymentAccounts<'info>,
+    pub bubblegum: BubblegumAccounts<'info>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -80,93 +44,74 @@ pub struct SwapCnftArgs {
     pub index: u32,
     pub pos: u8,
     pub metadata: AnchorMetadataArgs,
+    /// Proof for the asset id, only needed when the pool has an asset allowlist.
+    pub asset_proof: Vec<[u8; 32]>,
 }
 
-pub fn handler<'info>(
+impl SwapCnftArgs {
+    /// The leaf being sold, checked against `proof`.
+    pub fn into_leaf<'a, 'info>(
+        self,
+        proof: &'a [AccountInfo<'info>],
+    ) -> Result<CnftLeaf<'a, 'info>> {
+        Ok(CnftLeaf {
+            root: self.root,
+            data_hash: self.data_hash,
+            creator_hash: self.creator_hash,
+            nonce: self.nonce,
+            index: self.index,
+            metadata: MetadataArgs::try_from(self.metadata)?,
+            asset_proof: self.asset_proof,
+            proof,
+        })
+    }
+}
+
+pub(crate) fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, SwapCnft<'info>>,
     args: SwapCnftArgs,
 ) -> Result<()> {
-    let collections = &ctx.accounts.pool.collections;
-
-    let mpl_token_standard = args.metadata.token_standard.map(|ts| ts.convert());
-    let mpl_collection = args.metadata.collection.map(|c| c.convert());
-    let mpl_creators = args.metadata.creators.iter().map(|c| c.convert()).collect();
-    let mpl_token_program_version = args.metadata.token_program_version.convert();
-    let mpl_uses = args.metadata.uses.map(|u| u.convert());
-
-    let metadata = MetadataArgs {
-        name: args.metadata.name,
-        symbol: args.metadata.symbol,
-        uri: args.metadata.uri,
-        seller_fee_basis_points: args.metadata.seller_fee_basis_points,
-        primary_sale_happened: args.metadata.primary_sale_happened,
-        is_mutable: args.metadata.is_mutable,
-        edition_nonce: args.metadata.edition_nonce,
-        token_standard: mpl_token_standard,
-        collection: mpl_collection,
-        uses: mpl_uses,
-        token_program_version: mpl_token_program_version,
-        creators: mpl_creators,
-    };
+    let pos = args.pos;
+    let leaf = args.into_leaf(ctx.remaining_accounts)?;
 
-    // Verification if legit
-    let incoming_data_hash = hash_metadata(&metadata)?;
-    if incoming_data_hash != args.data_hash {
-        return Err(error!(ErrorCode::InvalidDataHash));
+    if ctx.accounts.pool.burn_mode {
+        ctx.accounts
+            .bubblegum
+            .admit_cnft(&ctx.accounts.pool, &leaf)?;
+        ctx.accounts.bubblegum.burn(
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            BurnInstructionArgs {
+                root: leaf.root,
+                data_hash: leaf.data_hash,
+                creator_hash: leaf.creator_hash,
+                nonce: leaf.nonce,
+                index: leaf.index,
+            },
+            leaf.proof,
+        )?;
+    } else {
+        let (Some(nft_store), Some(nft_store_bump)) =
+            (&mut ctx.accounts.nft_store, ctx.bumps.nft_store)
+        else {
+            return Err(error!(ErrorCode::MissingNftStore));
+        };
+        ctx.accounts.bubblegum.store_cnft(
+            &ctx.accounts.pool,
+            &leaf,
+            &ctx.accounts.authority,
+            nft_store,
+            nft_store_bump,
+            &ctx.accounts.system_program,
+        )?;
+        ctx.accounts.pool.add_item(pos);
     }
 
-    if let Some(collection) = &metadata.collection {
-        if !collections.contains(&collection.key) {
-            return Err(error!(ErrorCode::InvalidCollection));
-        }
-    }
-    TransferCpi::new(
-        &ctx.accounts.bubblegum_program,
-        TransferCpiAccounts {
-            tree_config: &ctx.accounts.tree_authority.to_account_info(),
-            leaf_owner: (&ctx.accounts.authority.to_account_info(), true),
-            leaf_delegate: (&ctx.accounts.authority.to_account_info(), false),
-            new_leaf_owner: &ctx.accounts.nft_store.to_account_info(),
-            merkle_tree: &ctx.accounts.merkle_tree.to_account_info(),
-            log_wrapper: &ctx.accounts.log_wrapper.to_account_info(),
-            compression_program: &ctx.accounts.compression_program.to_account_info(),
-            system_program: &ctx.accounts.system_program.to_account_info(),
-        },
-        TransferInstructionArgs {
-            root: args.root,
-            data_hash: args.data_hash,
-            creator_hash: args.creator_hash,
-            nonce: args.nonce,
-            index: args.index,
-        },
-    )
-    .invoke_with_remaining_accounts(
-        ctx.remaining_accounts
-            .iter()
-            .map(|account| (account, false, false))
-            .collect::<Vec<_>>()
-            .as_slice(),
+    ctx.accounts.payment.pay_seller(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
     )?;
 
-    let pool = &mut ctx.accounts.pool;
-    let amount = pool.price * 10u64.pow(ctx.accounts.mint.decimals as u32);
-    let pool_authority = pool.authority.key();
-    let pool_seeds = &[
-        b"pool".as_ref(),
-        pool_authority.as_ref(),
-        &[ctx.accounts.pool.bump],
-    ];
-    let pool_signer = &[&pool_seeds[..]];
-
-    let transfer_accounts = Transfer {
-        authority: ctx.accounts.authority.to_account_info(),
-        from: ctx.accounts.pool_token_account.to_account_info(),
-        to: ctx.accounts.authority_token_account.to_account_info(),
-    };
-    let token_program = ctx.accounts.token_program.to_account_info();
-    let transfer_context =
-        CpiContext::new_with_signer(token_program, transfer_accounts, pool_signer);
-    token::transfer(transfer_context, amount)?;
-
     Ok(())
 }
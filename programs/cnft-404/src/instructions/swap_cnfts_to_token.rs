@@ -0,0 +1,82 @@
+use crate::batch::{init_nft_store, split_leaf_accounts};
+use crate::bubblegum::*;
+use crate::errors::ErrorCode;
+use crate::instructions::SwapCnftArgs;
+use crate::math::sale_payout;
+use crate::payment::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SwapCnfts<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, constraint = !pool.burn_mode @ ErrorCode::BurnModeEnabled)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    pub payment: PaymentAccounts<'info>,
+    pub bubblegum: BubblegumAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sells several cnfts from one tree for a single payout of at least
+/// `min_amount_out`. The remaining accounts hold, for each leaf, its nft
+/// store followed by `proof_lengths[i]` proof nodes. The stores are created
+/// here and may already hold lamports.
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapCnfts<'info>>,
+    leaves: Vec<SwapCnftArgs>,
+    proof_lengths: Vec<u8>,
+    min_amount_out: u64,
+) -> Result<()> {
+    if leaves.len() != proof_lengths.len() {
+        return Err(error!(ErrorCode::InvalidLeafAccounts));
+    }
+    let leaf_accounts = split_leaf_accounts(ctx.remaining_accounts, &proof_lengths)?;
+
+    let authority = ctx.accounts.authority.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let pool_key = ctx.accounts.pool.key();
+    let count = leaves.len();
+    for (leaf, accounts) in leaves.into_iter().zip(leaf_accounts) {
+        let pos = leaf.pos;
+        let leaf = leaf.into_leaf(accounts.proof)?;
+        let asset_id = ctx.accounts.bubblegum.take_cnft(
+            &ctx.accounts.pool,
+            &leaf,
+            &authority,
+            accounts.nft_store,
+            &system_program,
+        )?;
+        init_nft_store(
+            accounts.nft_store,
+            &authority,
+            &system_program,
+            &pool_key,
+            pos,
+            |bump| NftStore {
+                asset_id,
+                bump,
+                kind: AssetKind::Compressed,
+            },
+        )?;
+
+        ctx.accounts.pool.add_item(pos);
+    }
+
+    let payment_mint = ctx.accounts.payment.mint_key();
+    let pool = &mut ctx.accounts.pool;
+    let amount = sale_payout(
+        pool.price_for(&payment_mint)?,
+        count,
+        pool.fee_bps,
+        min_amount_out,
+    )?;
+    ctx.accounts.payment.pay_out(
+        pool,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        amount,
+    )?;
+
+    pool.debit(&payment_mint, amount)
+}
@@ -0,0 +1,51 @@
+use crate::core_asset::*;
+use crate::errors::ErrorCode;
+use crate::payment::*;
+use crate::pool_asset::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(pos: u8)]
+pub struct SwapCoreAsset<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, constraint = !pool.burn_mode @ ErrorCode::BurnModeEnabled)]
+    pub pool: Account<'info, HybridPoolConfig>,
+    #[account(
+        init,
+        seeds = [b"cnft".as_ref(),pool.key().as_ref(),&pos.to_le_bytes()],
+        bump,
+        space = NftStore::LEN,
+        payer = authority,
+    )]
+    pub nft_store: Account<'info, NftStore>,
+    pub payment: PaymentAccounts<'info>,
+    pub core: CoreAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<SwapCoreAsset>,
+    pos: u8,
+    asset_proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    store_asset(
+        &ctx.accounts.core,
+        &ctx.accounts.pool,
+        &mut ctx.accounts.nft_store,
+        ctx.bumps.nft_store,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        &asset_proof,
+    )?;
+
+    ctx.accounts.payment.pay_seller(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+    )?;
+    ctx.accounts.pool.add_item(pos);
+
+    Ok(())
+}
@@ -1,10 +1,9 @@
-use anchor_lang::{prelude::*, solana_program::sysvar};
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
-};
-use arrayref::array_ref;
+use anchor_lang::prelude::*;
 
+use crate::bubblegum::*;
+use crate::errors::ErrorCode;
+use crate::math::{fee_amount, total_price};
+use crate::payment::*;
 use crate::{CnftClaimCoupon, HybridPoolConfig};
 
 #[derive(Accounts)]
@@ -13,68 +12,81 @@ pub struct SwapTokenToCnft<'info> {
     pub authority: Signer<'info>,
     #[account(mut)]
     pub pool: Account<'info, HybridPoolConfig>,
-    #[account(constraint = pool.token == mint.key())]
-    pub mint: Account<'info, Mint>,
-    #[
-        account(
-            mut,
-            associated_token::mint = mint,
-            associated_token::authority = pool,
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        associated_token::authority =  authority,
-        associated_token::mint = mint,
-    )]
-    pub authority_token_account: Account<'info, TokenAccount>,
+    pub payment: PaymentAccounts<'info>,
 
+    // not needed when the cnft is minted straight to the buyer
     #[account(
-        init,
-        space = 4,
+        init_if_needed,
+        space = CnftClaimCoupon::LEN,
         payer = authority,
         seeds = [b"cnft_claim_coupon".as_ref(),authority.key().as_ref()],
         bump,
     )]
-    pub cnft_claim_coupon: Account<'info, CnftClaimCoupon>,
-    pub token_program: Program<'info, Token>,
-
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    #[account(address = sysvar::slot_hashes::id())]
-    /// CHECK: This is a sysvar
-    pub recent_slot_hashes: UncheckedAccount<'info>,
+    pub cnft_claim_coupon: Option<Account<'info, CnftClaimCoupon>>,
+    // only needed when the pool mints, the collection accounts only when it
+    // mints into a collection
+    pub bubblegum: BubblegumAccounts<'info>,
+    pub collection: CollectionAccounts<'info>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<SwapTokenToCnft>) -> Result<()> {
+pub(crate) fn handler(ctx: Context<SwapTokenToCnft>, quantity: u8) -> Result<()> {
+    if quantity == 0 {
+        return Err(error!(ErrorCode::AmountTooSmall));
+    }
+
+    let payment = &ctx.accounts.payment;
+    let payment_mint = payment.mint_key();
     let pool = &mut ctx.accounts.pool;
-    let amount = pool.price * 10u64.pow(ctx.accounts.mint.decimals as u32);
 
-    let transfer_accounts = Transfer {
-        authority: ctx.accounts.authority.to_account_info(),
-        from: ctx.accounts.authority_token_account.to_account_info(),
-        to: ctx.accounts.pool_token_account.to_account_info(),
+    let price = total_price(pool.price_for(&payment_mint)?, quantity as usize)?;
+    let amount = price
+        .checked_add(fee_amount(price, pool.fee_bps)?)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+    let received = payment.pay_in(
+        pool,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        amount,
+    )?;
+    pool.credit(&payment_mint, received)?;
+
+    // held cnfts are handed out first, a minting pool covers the rest
+    let drawn = if pool.burn_mode {
+        0
+    } else if pool.mints_on_demand() {
+        pool.undrawn_items().min(quantity as usize) as u8
+    } else {
+        quantity
     };
-    let token_program = ctx.accounts.token_program.to_account_info();
-    let transfer_context = CpiContext::new(token_program, transfer_accounts);
-    token::transfer(transfer_context, amount)?;
 
-    //simple Random Generator
-    let recent_slothashes = &ctx.accounts.recent_slot_hashes;
-    let data = recent_slothashes.data.borrow();
-    let most_recent = array_ref![data, 12, 8];
+    if drawn < quantity {
+        let authority = ctx.accounts.authority.to_account_info();
+        for _ in drawn..quantity {
+            ctx.accounts.bubblegum.mint(
+                pool,
+                &ctx.accounts.collection,
+                &authority,
+                &authority,
+                &ctx.accounts.system_program,
+            )?;
+        }
+    }
 
-    let clock = Clock::get()?;
-    let seed = u64::from_le_bytes(*most_recent).saturating_sub(clock.unix_timestamp as u64);
-    let max_result = 255;
-    let result = seed as u8 % max_result + 1;
-    let nearest = pool
-        .items
-        .iter()
-        .min_by_key(|&&item| (item as u8 - result as u8))
-        .unwrap_or(&result);
-    ctx.accounts.cnft_claim_coupon.coupon = *nearest;
-    ctx.accounts.cnft_claim_coupon.bump = ctx.bumps.cnft_claim_coupon;
+    if drawn == 0 {
+        return Ok(());
+    }
+
+    let (Some(cnft_claim_coupon), Some(coupon_bump)) = (
+        &mut ctx.accounts.cnft_claim_coupon,
+        ctx.bumps.cnft_claim_coupon,
+    ) else {
+        return Err(error!(ErrorCode::MissingCoupon));
+    };
+    // the positions are drawn by `resolve_coupon` once a later slot hash
+    // exists, so the outcome is unknown while the purchase can still be aborted
+    pool.commit_draws(drawn)?;
+    cnft_claim_coupon.commit_draws(pool.key(), coupon_bump, drawn, Clock::get()?.slot)?;
 
     Ok(())
 }
@@ -0,0 +1,116 @@
+use crate::bubblegum::token_metadata;
+use crate::errors::ErrorCode;
+use crate::pool_asset::PoolAsset;
+use crate::state::*;
+use crate::validation::verify_legacy_admission;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata::instructions::{
+    TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs,
+};
+use anchor_spl::metadata::MetadataAccount;
+use anchor_spl::token::{Mint, Token};
+
+/// Token Metadata nft or pnft being moved, with the token accounts on both
+/// sides. The token records and rules are only needed for pnfts.
+#[derive(Accounts)]
+pub struct LegacyAccounts<'info> {
+    #[account(constraint = mint.supply == 1 && mint.decimals == 0 @ ErrorCode::NotAnNft)]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"metadata".as_ref(), token_metadata::ID.as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata::ID
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+    ///CHECK: Checked in CPI
+    pub edition: UncheckedAccount<'info>,
+    #[account(mut)]
+    ///CHECK: Checked in CPI
+    pub source_token: UncheckedAccount<'info>,
+    // created by token metadata when the new owner has no token account yet
+    #[account(mut)]
+    ///CHECK: Checked in CPI
+    pub destination_token: UncheckedAccount<'info>,
+    #[account(mut)]
+    ///CHECK: Checked in CPI
+    pub token_record: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    ///CHECK: Checked in CPI
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+    ///CHECK: Checked in CPI
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    ///CHECK: Checked in CPI
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+    #[account(address = token_metadata::ID)]
+    ///CHECK: Token Metadata program
+    pub token_metadata_program: UncheckedAccount<'info>,
+    #[account(address = sysvar::instructions::ID)]
+    ///CHECK: Instructions sysvar
+    pub sysvar_instructions: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> PoolAsset<'info> for LegacyAccounts<'info> {
+    const KIND: AssetKind = AssetKind::Legacy;
+
+    fn asset_id(&self) -> Pubkey {
+        self.mint.key()
+    }
+
+    fn verify_admission(&self, pool: &HybridPoolConfig) -> Result<()> {
+        verify_legacy_admission(&self.metadata, pool)
+    }
+
+    // goes through Token Metadata so pnft rules are enforced
+    fn transfer(
+        &self,
+        owner: &AccountInfo<'info>,
+        new_owner: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        TransferV1Cpi::new(
+            &self.token_metadata_program,
+            TransferV1CpiAccounts {
+                token: &self.source_token,
+                token_owner: owner,
+                destination_token: &self.destination_token,
+                destination_owner: new_owner,
+                mint: &self.mint.to_account_info(),
+                metadata: &self.metadata.to_account_info(),
+                edition: Some(&self.edition),
+                token_record: self.token_record.as_ref().map(|record| record.as_ref()),
+                destination_token_record: self
+                    .destination_token_record
+                    .as_ref()
+                    .map(|record| record.as_ref()),
+                authority: owner,
+                payer,
+                system_program,
+                sysvar_instructions: &self.sysvar_instructions,
+                spl_token_program: &self.token_program.to_account_info(),
+                spl_ata_program: &self.associated_token_program.to_account_info(),
+                authorization_rules_program: self
+                    .authorization_rules_program
+                    .as_ref()
+                    .map(|program| program.as_ref()),
+                authorization_rules: self
+                    .authorization_rules
+                    .as_ref()
+                    .map(|rules| rules.as_ref()),
+            },
+            TransferV1InstructionArgs {
+                amount: 1,
+                authorization_data: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,20 @@
 use anchor_lang::prelude::*;
+pub mod allowlist;
+pub mod batch;
+pub mod bubblegum;
+pub mod core_asset;
 pub mod errors;
 pub mod instructions;
+pub mod legacy_asset;
+pub mod liquidity;
+pub mod math;
+pub mod payment;
+pub mod pool_asset;
+pub mod random;
+pub mod sol_vault;
 pub mod state;
+pub mod transfer_fee;
+pub mod validation;
 
 pub use error::ErrorCode;
 pub use instructions::*;
@@ -18,8 +31,73 @@ pub mod drip_rewards {
         init_pool::handler(ctx, collections, price)
     }
 
-    pub fn deposit_tokens(ctx: Context<DepositTokens>) -> Result<()> {
-        deposit_token::handler(ctx)
+    pub fn add_accepted_token(ctx: Context<AddAcceptedToken>, price: u64) -> Result<()> {
+        add_accepted_token::handler(ctx, price)
+    }
+
+    pub fn set_creators(ctx: Context<SetCreators>, creators: Vec<Pubkey>) -> Result<()> {
+        set_creators::handler(ctx, creators)
+    }
+
+    pub fn set_asset_allowlist_root(
+        ctx: Context<SetAssetAllowlistRoot>,
+        root: [u8; 32],
+    ) -> Result<()> {
+        set_asset_allowlist_root::handler(ctx, root)
+    }
+
+    pub fn set_metadata_rules(ctx: Context<SetMetadataRules>, rules: MetadataRules) -> Result<()> {
+        set_metadata_rules::handler(ctx, rules)
+    }
+
+    pub fn set_reroll_config(
+        ctx: Context<SetRerollConfig>,
+        reroll_fee_bps: u16,
+        max_rerolls: Option<u8>,
+    ) -> Result<()> {
+        set_reroll_config::handler(ctx, reroll_fee_bps, max_rerolls)
+    }
+
+    pub fn set_cnft_swap_fee(ctx: Context<SetCnftSwapFee>, fee_bps: u16) -> Result<()> {
+        set_cnft_swap_fee::handler(ctx, fee_bps)
+    }
+
+    pub fn set_premium_multiplier(
+        ctx: Context<SetPremiumMultiplier>,
+        multiplier_bps: u32,
+    ) -> Result<()> {
+        set_premium_multiplier::handler(ctx, multiplier_bps)
+    }
+
+    pub fn set_tier_weights(
+        ctx: Context<SetTierWeights>,
+        weights: [u16; TIER_COUNT],
+    ) -> Result<()> {
+        set_tier_weights::handler(ctx, weights)
+    }
+
+    pub fn set_item_tier(ctx: Context<SetItemTier>, pos: u8, tier: Tier) -> Result<()> {
+        set_item_tier::handler(ctx, pos, tier)
+    }
+
+    pub fn create_pool_tree(
+        ctx: Context<CreatePoolTree>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        create_pool_tree::handler(ctx, max_depth, max_buffer_size)
+    }
+
+    pub fn enable_minting(ctx: Context<EnableMinting>, template: MintTemplate) -> Result<()> {
+        enable_minting::handler(ctx, template, false)
+    }
+
+    pub fn enable_burn_mode(ctx: Context<EnableMinting>, template: MintTemplate) -> Result<()> {
+        enable_minting::handler(ctx, template, true)
+    }
+
+    pub fn deposit_tokens(ctx: Context<DepositTokens>, amount: u64) -> Result<()> {
+        deposit_token::handler(ctx, amount)
     }
 
     pub fn deposit_cnft<'info>(
@@ -29,8 +107,51 @@ pub mod drip_rewards {
         deposit_cnft::handler(ctx, args)
     }
 
-    pub fn swap_token_to_cnft<'info>(ctx: Context<SwapTokenToCnft>) -> Result<()> {
-        swap_token_to_cnft::handler(ctx)
+    pub fn deposit_cnfts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositCnfts<'info>>,
+        leaves: Vec<DepositCnftArgs>,
+        proof_lengths: Vec<u8>,
+    ) -> Result<()> {
+        deposit_cnfts::handler(ctx, leaves, proof_lengths)
+    }
+
+    pub fn deposit_core_asset<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositCoreAsset<'info>>,
+        pos: u8,
+        asset_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        deposit_core_asset::handler(ctx, pos, asset_proof)
+    }
+
+    pub fn swap_token_to_cnft(ctx: Context<SwapTokenToCnft>, quantity: u8) -> Result<()> {
+        swap_token_to_cnft::handler(ctx, quantity)
+    }
+
+    pub fn buy_specific_cnft<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuySpecificCnft<'info>>,
+        args: BuySpecificCnftArgs,
+    ) -> Result<()> {
+        buy_specific_cnft::handler(ctx, args)
+    }
+
+    pub fn init_liquidity(ctx: Context<InitLiquidity>, fee_bps: u16) -> Result<()> {
+        init_liquidity::handler(ctx, fee_bps)
+    }
+
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
+        add_liquidity::handler(ctx, amount)
+    }
+
+    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, shares: u64) -> Result<()> {
+        remove_liquidity::handler(ctx, shares)
+    }
+
+    pub fn reroll(ctx: Context<Reroll>, pos: u8) -> Result<()> {
+        reroll::handler(ctx, pos)
+    }
+
+    pub fn resolve_coupon(ctx: Context<ResolveCoupon>) -> Result<()> {
+        resolve_coupon::handler(ctx)
     }
 
     pub fn claim_cnft<'info>(
@@ -40,10 +161,66 @@ pub mod drip_rewards {
         claim_cnft::handler(ctx, args)
     }
 
+    pub fn claim_cnfts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimCnfts<'info>>,
+        claims: Vec<ClaimCnftArgs>,
+        proof_lengths: Vec<u8>,
+    ) -> Result<()> {
+        claim_cnfts::handler(ctx, claims, proof_lengths)
+    }
+
     pub fn swap_cnft_to_token<'info>(
         ctx: Context<'_, '_, '_, 'info, SwapCnft<'info>>,
         args: SwapCnftArgs,
     ) -> Result<()> {
         swap_cnft_to_token::handler(ctx, args)
     }
+
+    pub fn swap_cnfts_to_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapCnfts<'info>>,
+        leaves: Vec<SwapCnftArgs>,
+        proof_lengths: Vec<u8>,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        swap_cnfts_to_token::handler(ctx, leaves, proof_lengths, min_amount_out)
+    }
+
+    pub fn swap_cnft_to_cnft<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapCnftToCnft<'info>>,
+        args: SwapCnftArgs,
+    ) -> Result<()> {
+        swap_cnft_to_cnft::handler(ctx, args)
+    }
+
+    pub fn claim_core_asset(ctx: Context<ClaimCoreAsset>, pos: u8) -> Result<()> {
+        claim_core_asset::handler(ctx, pos)
+    }
+
+    pub fn swap_core_asset_to_token(
+        ctx: Context<SwapCoreAsset>,
+        pos: u8,
+        asset_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        swap_core_asset_to_token::handler(ctx, pos, asset_proof)
+    }
+
+    pub fn deposit_legacy_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositLegacyNft<'info>>,
+        pos: u8,
+        asset_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        deposit_legacy_nft::handler(ctx, pos, asset_proof)
+    }
+
+    pub fn claim_legacy_nft(ctx: Context<ClaimLegacyNft>, pos: u8) -> Result<()> {
+        claim_legacy_nft::handler(ctx, pos)
+    }
+
+    pub fn swap_legacy_nft_to_token(
+        ctx: Context<SwapLegacyNft>,
+        pos: u8,
+        asset_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        swap_legacy_nft_to_token::handler(ctx, pos, asset_proof)
+    }
 }
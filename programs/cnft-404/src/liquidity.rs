@@ -0,0 +1,113 @@
+use crate::errors::ErrorCode;
+use crate::math::{shares_for_deposit, total_price};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken, Create,
+};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, MintTo};
+
+/// Mints `shares` LP tokens to `to`, signed by the pool.
+pub fn mint_shares<'info>(
+    pool: &Account<'info, HybridPoolConfig>,
+    lp_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    lp_token_program: &Program<'info, Token>,
+    shares: u64,
+) -> Result<()> {
+    let pool_authority = pool.authority.key();
+    let pool_seeds = &[b"pool".as_ref(), pool_authority.as_ref(), &[pool.bump]];
+    let pool_signer = &[&pool_seeds[..]];
+
+    let mint_accounts = MintTo {
+        mint: lp_mint.to_account_info(),
+        to,
+        authority: pool.to_account_info(),
+    };
+    let mint_context = CpiContext::new_with_signer(
+        lp_token_program.to_account_info(),
+        mint_accounts,
+        pool_signer,
+    );
+    token_interface::mint_to(mint_context, shares)
+}
+
+/// Accounts receiving LP shares for nfts provided to a pool with liquidity
+/// providers. Pools without liquidity providers leave them all out.
+///
+/// A nested struct cannot see the pool or the provider, so the addresses are
+/// checked against them in `mint_item_shares`, which also creates a missing
+/// LP token account at the provider's expense.
+#[derive(Accounts)]
+pub struct LpAccounts<'info> {
+    #[account(mut)]
+    pub lp_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    /// CHECK: Must be the provider's associated token account for `lp_mint`
+    pub user_lp_account: Option<UncheckedAccount<'info>>,
+    pub lp_token_program: Option<Program<'info, Token>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> LpAccounts<'info> {
+    /// Mints LP shares to `provider` for `count` nfts joining the pool, valued
+    /// at the pool price. Call before the nfts are added to `pool.items`.
+    pub fn mint_item_shares(
+        &self,
+        pool: &Account<'info, HybridPoolConfig>,
+        count: usize,
+        provider: &Signer<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let (
+            Some(lp_mint),
+            Some(user_lp_account),
+            Some(lp_token_program),
+            Some(associated_token_program),
+        ) = (
+            &self.lp_mint,
+            &self.user_lp_account,
+            &self.lp_token_program,
+            &self.associated_token_program,
+        )
+        else {
+            return Err(error!(ErrorCode::MissingLiquidityAccounts));
+        };
+        if lp_mint.key() != pool.lp_mint {
+            return Err(error!(anchor_lang::error::ErrorCode::ConstraintAddress));
+        }
+
+        let address = get_associated_token_address_with_program_id(
+            provider.key,
+            &lp_mint.key(),
+            &lp_token_program.key(),
+        );
+        if user_lp_account.key() != address {
+            return Err(error!(anchor_lang::error::ErrorCode::ConstraintAssociated));
+        }
+        if user_lp_account.data_is_empty() {
+            associated_token::create(CpiContext::new(
+                associated_token_program.to_account_info(),
+                Create {
+                    payer: provider.to_account_info(),
+                    associated_token: user_lp_account.to_account_info(),
+                    authority: provider.to_account_info(),
+                    mint: lp_mint.to_account_info(),
+                    system_program: system_program.clone(),
+                    token_program: lp_token_program.to_account_info(),
+                },
+            ))?;
+        }
+
+        let value = total_price(pool.price, count)?;
+        let shares = shares_for_deposit(value, pool.value()?, lp_mint.supply)?;
+        mint_shares(
+            pool,
+            lp_mint,
+            user_lp_account.to_account_info(),
+            lp_token_program,
+            shares,
+        )
+    }
+}
@@ -0,0 +1,62 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Converts a whole-token amount into base units for a mint with `decimals`,
+/// e.g. to build the `price` passed to `init_pool`.
+pub fn to_base_units(amount: u64, decimals: u8) -> Result<u64> {
+    10u64
+        .checked_pow(decimals as u32)
+        .and_then(|factor| amount.checked_mul(factor))
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+/// Total cost in base units of `count` items at `price` base units each.
+pub fn total_price(price: u64, count: usize) -> Result<u64> {
+    u64::try_from(count)
+        .ok()
+        .and_then(|count| price.checked_mul(count))
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+/// Swap fee charged on `amount` at `fee_bps` basis points, rounded up.
+pub fn fee_amount(amount: u64, fee_bps: u16) -> Result<u64> {
+    let fee = (amount as u128 * fee_bps as u128).div_ceil(10_000);
+    u64::try_from(fee).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+/// What a seller receives for `count` items at `price` base units each, less
+/// the swap fee at `fee_bps`. Fails when that is below `min_amount_out`.
+pub fn sale_payout(price: u64, count: usize, fee_bps: u16, min_amount_out: u64) -> Result<u64> {
+    let price = total_price(price, count)?;
+    let amount = price
+        .checked_sub(fee_amount(price, fee_bps)?)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+    if amount < min_amount_out {
+        return Err(error!(ErrorCode::SlippageExceeded));
+    }
+    Ok(amount)
+}
+
+/// `price` scaled by `multiplier_bps` basis points, rounded up.
+pub fn premium_price(price: u64, multiplier_bps: u32) -> Result<u64> {
+    let value = (price as u128 * multiplier_bps as u128).div_ceil(10_000);
+    u64::try_from(value).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+/// `amount * numerator / denominator`, rounded down.
+pub fn pro_rata(amount: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    if denominator == 0 {
+        return Err(error!(ErrorCode::MathOverflow));
+    }
+    let value = amount as u128 * numerator as u128 / denominator as u128;
+    u64::try_from(value).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+/// LP shares minted for adding `value` to a pool worth `pool_value` with
+/// `supply` shares outstanding. The first deposit mints shares 1:1.
+pub fn shares_for_deposit(value: u64, pool_value: u64, supply: u64) -> Result<u64> {
+    if supply == 0 {
+        return Ok(value);
+    }
+    pro_rata(value, supply, pool_value)
+}
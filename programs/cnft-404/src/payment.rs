@@ -0,0 +1,223 @@
+use crate::errors::ErrorCode;
+use crate::math::sale_payout;
+use crate::sol_vault;
+use crate::state::*;
+use crate::transfer_fee::net_amount;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken, Create,
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+/// Accounts moving a pool token between a user and the pool. Tokens go
+/// through the pool's associated token account, native SOL through the
+/// `sol_vault` PDA. Native SOL payments leave every token account out.
+///
+/// A nested struct cannot see the pool or the user, so the addresses are
+/// checked against them in `pay_in` and `pay_out`. A missing associated
+/// token account on the receiving side is created there, paid for by the
+/// user.
+#[derive(Accounts)]
+pub struct PaymentAccounts<'info> {
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    /// CHECK: Must be the pool's associated token account for `mint`
+    pub pool_token_account: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    /// CHECK: Must be the user's associated token account for `mint`
+    pub user_token_account: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub sol_vault: Option<SystemAccount<'info>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> PaymentAccounts<'info> {
+    /// Mint being paid in. Native SOL has no mint account.
+    pub fn mint_key(&self) -> Pubkey {
+        self.mint.as_ref().map_or(NATIVE_MINT, |mint| mint.key())
+    }
+
+    /// Moves `amount` from `user` into the pool and returns the amount the
+    /// pool received, which is less than `amount` when the mint withholds a
+    /// transfer fee.
+    pub fn pay_in(
+        &self,
+        pool: &Account<'info, HybridPoolConfig>,
+        user: &Signer<'info>,
+        system_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<u64> {
+        if !pool.accepts(&self.mint_key()) {
+            return Err(error!(ErrorCode::UnsupportedToken));
+        }
+
+        if self.mint_key() == NATIVE_MINT {
+            let (sol_vault, _) = self.sol_vault(pool)?;
+            sol_vault::deposit(
+                user.to_account_info(),
+                sol_vault.to_account_info(),
+                system_program.clone(),
+                amount,
+            )?;
+            return Ok(amount);
+        }
+
+        let (Some(mint), Some(token_program)) = (&self.mint, &self.token_program) else {
+            return Err(error!(ErrorCode::MissingPaymentAccounts));
+        };
+        let pool_token_account = self.token_account(
+            &self.pool_token_account,
+            &pool.to_account_info(),
+            user,
+            system_program,
+        )?;
+        let user_token_account =
+            self.token_account(&self.user_token_account, user, user, system_program)?;
+
+        let transfer_accounts = TransferChecked {
+            authority: user.to_account_info(),
+            from: user_token_account,
+            mint: mint.to_account_info(),
+            to: pool_token_account,
+        };
+        let transfer_context = CpiContext::new(token_program.to_account_info(), transfer_accounts);
+        token_interface::transfer_checked(transfer_context, amount, mint.decimals)?;
+
+        net_amount(mint, amount)
+    }
+
+    /// Moves `amount` from the pool to `user`, failing if the vault cannot cover it.
+    pub fn pay_out(
+        &self,
+        pool: &Account<'info, HybridPoolConfig>,
+        user: &Signer<'info>,
+        system_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        if !pool.accepts(&self.mint_key()) {
+            return Err(error!(ErrorCode::UnsupportedToken));
+        }
+
+        if self.mint_key() == NATIVE_MINT {
+            let (sol_vault, sol_vault_bump) = self.sol_vault(pool)?;
+            return sol_vault::withdraw(
+                sol_vault.to_account_info(),
+                user.to_account_info(),
+                system_program.clone(),
+                pool.key(),
+                sol_vault_bump,
+                amount,
+            );
+        }
+
+        let (Some(mint), Some(token_program)) = (&self.mint, &self.token_program) else {
+            return Err(error!(ErrorCode::MissingPaymentAccounts));
+        };
+        let pool_token_account = self.token_account(
+            &self.pool_token_account,
+            &pool.to_account_info(),
+            user,
+            system_program,
+        )?;
+        let user_token_account =
+            self.token_account(&self.user_token_account, user, user, system_program)?;
+
+        let vault = TokenAccount::try_deserialize(&mut &pool_token_account.try_borrow_data()?[..])?;
+        if vault.amount < amount {
+            return Err(error!(ErrorCode::InsufficientPoolFunds));
+        }
+
+        let pool_authority = pool.authority.key();
+        let pool_seeds = &[b"pool".as_ref(), pool_authority.as_ref(), &[pool.bump]];
+        let pool_signer = &[&pool_seeds[..]];
+
+        let transfer_accounts = TransferChecked {
+            authority: pool.to_account_info(),
+            from: pool_token_account,
+            mint: mint.to_account_info(),
+            to: user_token_account,
+        };
+        let transfer_context = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer,
+        );
+        token_interface::transfer_checked(transfer_context, amount, mint.decimals)
+    }
+
+    /// Pays `seller` the pool price of one nft less the pool fee, and
+    /// debits it from the pool.
+    pub fn pay_seller(
+        &self,
+        pool: &mut Account<'info, HybridPoolConfig>,
+        seller: &Signer<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let payment_mint = self.mint_key();
+        let amount = sale_payout(pool.price_for(&payment_mint)?, 1, pool.fee_bps, 0)?;
+        self.pay_out(pool, seller, system_program, amount)?;
+
+        pool.debit(&payment_mint, amount)
+    }
+
+    /// The pool's `sol_vault` PDA and its bump.
+    fn sol_vault(
+        &self,
+        pool: &Account<'info, HybridPoolConfig>,
+    ) -> Result<(&SystemAccount<'info>, u8)> {
+        let Some(sol_vault) = &self.sol_vault else {
+            return Err(error!(ErrorCode::MissingPaymentAccounts));
+        };
+        let (address, bump) =
+            Pubkey::find_program_address(&[b"sol_vault", pool.key().as_ref()], &crate::ID);
+        if sol_vault.key() != address {
+            return Err(error!(anchor_lang::error::ErrorCode::ConstraintSeeds));
+        }
+        Ok((sol_vault, bump))
+    }
+
+    /// Checks that `account` is the associated token account of `owner` for
+    /// the payment mint, creating it at `payer`'s expense when it does not
+    /// exist yet.
+    fn token_account(
+        &self,
+        account: &Option<UncheckedAccount<'info>>,
+        owner: &AccountInfo<'info>,
+        payer: &Signer<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<AccountInfo<'info>> {
+        let (Some(account), Some(mint), Some(token_program)) =
+            (account, &self.mint, &self.token_program)
+        else {
+            return Err(error!(ErrorCode::MissingPaymentAccounts));
+        };
+        let address = get_associated_token_address_with_program_id(
+            owner.key,
+            &mint.key(),
+            &token_program.key(),
+        );
+        if account.key() != address {
+            return Err(error!(anchor_lang::error::ErrorCode::ConstraintAssociated));
+        }
+
+        if account.data_is_empty() {
+            let Some(associated_token_program) = &self.associated_token_program else {
+                return Err(error!(ErrorCode::MissingPaymentAccounts));
+            };
+            associated_token::create(CpiContext::new(
+                associated_token_program.to_account_info(),
+                Create {
+                    payer: payer.to_account_info(),
+                    associated_token: account.to_account_info(),
+                    authority: owner.clone(),
+                    mint: mint.to_account_info(),
+                    system_program: system_program.clone(),
+                    token_program: token_program.to_account_info(),
+                },
+            ))?;
+        }
+
+        Ok(account.to_account_info())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::state::*;
+use crate::validation::verify_allowlisted_asset;
+use anchor_lang::prelude::*;
+
+/// A Core asset or Token Metadata nft held in an nft store. Both kinds go
+/// through the same deposit, sell and claim flows and only differ in how the
+/// asset is admitted and moved.
+pub trait PoolAsset<'info> {
+    /// Kind recorded on the nft store holding the asset.
+    const KIND: AssetKind;
+
+    /// Id recorded on the nft store: the Core asset or the nft mint.
+    fn asset_id(&self) -> Pubkey;
+
+    /// Checks the asset against the pool admission rules.
+    fn verify_admission(&self, pool: &HybridPoolConfig) -> Result<()>;
+
+    /// Moves the asset from `owner` to `new_owner`. `signer_seeds` signs for a
+    /// PDA `owner`.
+    fn transfer(
+        &self,
+        owner: &AccountInfo<'info>,
+        new_owner: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()>;
+}
+
+/// Admits the asset and moves it from `owner` into the freshly created
+/// `nft_store`. Deposits and sells both start here.
+pub fn store_asset<'info, A: PoolAsset<'info>>(
+    asset: &A,
+    pool: &HybridPoolConfig,
+    nft_store: &mut Account<'info, NftStore>,
+    nft_store_bump: u8,
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    asset_proof: &[[u8; 32]],
+) -> Result<()> {
+    let asset_id = asset.asset_id();
+    asset.verify_admission(pool)?;
+    verify_allowlisted_asset(&asset_id, asset_proof, pool)?;
+
+    let owner = owner.to_account_info();
+    asset.transfer(
+        &owner,
+        &nft_store.to_account_info(),
+        &owner,
+        &system_program.to_account_info(),
+        &[],
+    )?;
+    nft_store.set_inner(NftStore {
+        asset_id,
+        bump: nft_store_bump,
+        kind: A::KIND,
+    });
+
+    Ok(())
+}
+
+/// Moves the asset at `pos` out of its nft store to `new_owner`, signed by
+/// the store.
+pub fn release_asset<'info, A: PoolAsset<'info>>(
+    asset: &A,
+    pool: Pubkey,
+    pos: u8,
+    nft_store: &Account<'info, NftStore>,
+    new_owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let pos_bytes = pos.to_le_bytes();
+    let nft_store_seeds = &[
+        b"cnft".as_ref(),
+        pool.as_ref(),
+        &pos_bytes,
+        &[nft_store.bump],
+    ];
+    let nft_store_signer = &[&nft_store_seeds[..]];
+
+    let new_owner = new_owner.to_account_info();
+    asset.transfer(
+        &nft_store.to_account_info(),
+        &new_owner,
+        &new_owner,
+        &system_program.to_account_info(),
+        nft_store_signer,
+    )
+}
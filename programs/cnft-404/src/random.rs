@@ -0,0 +1,77 @@
+use crate::errors::ErrorCode;
+use crate::state::HybridPoolConfig;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use arrayref::array_ref;
+
+/// Removes `count` positions from `pool`, without replacement and weighted by
+/// tier, drawn from the hash of a slot after the draws were committed.
+/// `entropy` is mixed in so draws committed in the same slot differ.
+pub fn resolve_positions(
+    pool: &mut HybridPoolConfig,
+    count: u8,
+    slot_hash: &[u8; 32],
+    entropy: &[&[u8]],
+) -> Result<Vec<u8>> {
+    let mut positions = Vec::with_capacity(count as usize);
+    for draw in 0..count {
+        let draw = [draw];
+        let mut seeds = vec![slot_hash.as_ref()];
+        seeds.extend_from_slice(entropy);
+        seeds.push(&draw);
+        let hash = hashv(&seeds).to_bytes();
+
+        let index = pool
+            .pick_item(u64::from_le_bytes(*array_ref![hash, 0, 8]))
+            .ok_or_else(|| error!(ErrorCode::NoCnftsInPool))?;
+        positions.push(pool.take_item(index));
+    }
+
+    Ok(positions)
+}
+
+/// The hash a draw committed in some slot resolves from.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SlotHashAfter {
+    /// No slot after the commit has a hash yet.
+    Pending,
+    /// Hash of the first slot after the commit.
+    Ready([u8; 32]),
+    /// The commit slot has left the sysvar, so the first slot after it can
+    /// no longer be told apart.
+    Expired,
+}
+
+/// Looks up the hash of the first slot after `slot` in the SlotHashes sysvar
+/// data, which lists the most recent slots newest first. Nobody knows that
+/// hash when the draw is committed, so the outcome can neither be simulated
+/// nor aborted by the committing transaction.
+pub fn slot_hash_after(slot_hashes: &[u8], slot: u64) -> SlotHashAfter {
+    let len = slot_hashes
+        .get(..8)
+        .map_or(0, |len| u64::from_le_bytes(*array_ref![len, 0, 8]) as usize);
+    let entries = slot_hashes
+        .get(8..)
+        .unwrap_or_default()
+        .chunks_exact(40)
+        .take(len)
+        .map(|entry| {
+            (
+                u64::from_le_bytes(*array_ref![entry, 0, 8]),
+                *array_ref![entry, 8, 32],
+            )
+        });
+
+    let mut first_after = None;
+    for (entry_slot, hash) in entries {
+        if entry_slot <= slot {
+            return first_after.map_or(SlotHashAfter::Pending, SlotHashAfter::Ready);
+        }
+        first_after = Some(hash);
+    }
+
+    match first_after {
+        Some(_) => SlotHashAfter::Expired,
+        None => SlotHashAfter::Pending,
+    }
+}
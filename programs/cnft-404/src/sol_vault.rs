@@ -0,0 +1,69 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+/// Lamports to send so that `amount` lands in a vault holding `vault_lamports`.
+/// The vault is a plain system account, so the first deposit also covers its
+/// rent exemption.
+pub fn deposit_lamports(rent: &Rent, vault_lamports: u64, amount: u64) -> Result<u64> {
+    let rent = if vault_lamports == 0 {
+        rent.minimum_balance(0)
+    } else {
+        0
+    };
+    amount
+        .checked_add(rent)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+/// Fails unless `amount` can leave a vault holding `vault_lamports` while the
+/// vault stays rent exempt.
+pub fn check_withdrawal(rent: &Rent, vault_lamports: u64, amount: u64) -> Result<()> {
+    let remaining = vault_lamports
+        .checked_sub(amount)
+        .ok_or_else(|| error!(ErrorCode::InsufficientPoolFunds))?;
+    if remaining < rent.minimum_balance(0) {
+        return Err(error!(ErrorCode::InsufficientPoolFunds));
+    }
+    Ok(())
+}
+
+/// Moves `amount` lamports from a signer into the pool's sol vault.
+pub fn deposit<'info>(
+    from: AccountInfo<'info>,
+    sol_vault: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = deposit_lamports(&Rent::get()?, sol_vault.lamports(), amount)?;
+
+    let transfer_accounts = Transfer {
+        from,
+        to: sol_vault,
+    };
+    system_program::transfer(CpiContext::new(system_program, transfer_accounts), amount)
+}
+
+/// Moves `amount` lamports out of the pool's sol vault, signed by the vault PDA.
+pub fn withdraw<'info>(
+    sol_vault: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    pool: Pubkey,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    check_withdrawal(&Rent::get()?, sol_vault.lamports(), amount)?;
+
+    let sol_vault_seeds = &[b"sol_vault".as_ref(), pool.as_ref(), &[bump]];
+    let sol_vault_signer = &[&sol_vault_seeds[..]];
+
+    let transfer_accounts = Transfer {
+        from: sol_vault,
+        to,
+    };
+    system_program::transfer(
+        CpiContext::new_with_signer(system_program, transfer_accounts, sol_vault_signer),
+        amount,
+    )
+}
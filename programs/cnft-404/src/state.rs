@@ -1,25 +1,272 @@
+use crate::errors::ErrorCode;
+use crate::math::total_price;
 use anchor_lang::prelude::*;
 use mpl_bubblegum::types::Collection as BubblegumCollection;
 use mpl_bubblegum::types::Creator as BubblegumCreator;
+use mpl_bubblegum::types::MetadataArgs;
 use mpl_bubblegum::types::TokenProgramVersion as BubblegumTokenProgramVersion;
 use mpl_bubblegum::types::TokenStandard as BubblegumTokenStandard;
 use mpl_bubblegum::types::UseMethod as BubblegumUseMethod;
 use mpl_bubblegum::types::Uses as BubblegumUses;
 
 pub const MAX_COLLECTIONS: usize = 3;
+pub const MAX_CREATORS: usize = 5;
 pub const MAX_ITEMS_IN_POOL: usize = 256;
+pub const MAX_ACCEPTED_TOKENS: usize = 2;
+pub const MAX_COUPON_POSITIONS: usize = 10;
+pub const MAX_FEE_BPS: u16 = 10_000;
+/// Premium multiplier charging exactly the pool price.
+pub const BASE_MULTIPLIER_BPS: u32 = 10_000;
+// metaplex metadata field limits
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+pub const MAX_TREE_DEPTH: u32 = 30;
+/// Digits reserved for the number of a minted cnft, a tree holds at most
+/// 2^MAX_TREE_DEPTH.
+pub const MAX_MINT_NUMBER_DIGITS: usize = 10;
+/// Mint used for tokens priced in native SOL, either as `HybridPoolConfig.token`
+/// or as an accepted token.
+pub const NATIVE_MINT: Pubkey = anchor_spl::token::spl_token::native_mint::ID;
+
+/// Kind of nft an `NftStore` holds, so a pool can mix asset standards.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AssetKind {
+    #[default]
+    Compressed,
+    Core,
+    /// Token Metadata nft or pnft, held in a token account of the store.
+    Legacy,
+}
+
+/// Rarity tier of a pool position. Each position is drawn with the weight of
+/// its tier.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tier {
+    #[default]
+    Common,
+    Rare,
+    Legendary,
+}
+
+pub const TIER_COUNT: usize = 3;
 
 #[account]
 #[derive(Default)]
 pub struct NftStore {
     pub asset_id: Pubkey,
     pub bump: u8,
+    pub kind: AssetKind,
+}
+
+impl NftStore {
+    pub const LEN: usize = 8 + 32 + 1 + 1;
 }
 
 #[account]
+#[derive(Default)]
 pub struct CnftClaimCoupon {
     pub bump: u8,
-    pub coupon: u8,
+    pub pool: Pubkey,
+    /// Pool positions drawn for the holder that have not been claimed yet.
+    pub positions: Vec<u8>,
+    /// Number of times a position on the coupon was re-rolled.
+    pub rerolls: u8,
+    /// Slot the pending draws were committed in. They are drawn from the
+    /// hash of the first slot after it.
+    pub pending_slot: Option<u64>,
+    /// Positions still to be drawn by `resolve_coupon`.
+    pub pending_draws: u8,
+    /// Position `swap_cnft_to_cnft` took in for this coupon. It joins the
+    /// pool once the draw resolves, so it cannot be drawn back.
+    pub swapped_in: Option<u8>,
+}
+
+impl CnftClaimCoupon {
+    pub const LEN: usize = 8 + 1 + 32 + 4 + MAX_COUPON_POSITIONS + 1 + 1 + 8 + 1 + 1 + 1;
+
+    /// Commits `count` draws in `slot`, binding an empty coupon to `pool`.
+    pub fn commit_draws(&mut self, pool: Pubkey, bump: u8, count: u8, slot: u64) -> Result<()> {
+        if self.pending_slot.is_some() {
+            return Err(error!(ErrorCode::DrawPending));
+        }
+        if self.positions.is_empty() {
+            self.pool = pool;
+            self.bump = bump;
+        } else if self.pool != pool {
+            return Err(error!(ErrorCode::CouponForAnotherPool));
+        }
+
+        if self.positions.len() + count as usize > MAX_COUPON_POSITIONS {
+            return Err(error!(ErrorCode::TooManyCouponPositions));
+        }
+        self.pending_draws = count;
+        self.pending_slot = Some(slot);
+
+        Ok(())
+    }
+
+    /// Gives up `pos` for a position drawn after `slot`, counting the re-roll
+    /// against `max_rerolls`.
+    pub fn reroll(&mut self, pos: u8, slot: u64, max_rerolls: Option<u8>) -> Result<()> {
+        if self.pending_slot.is_some() {
+            return Err(error!(ErrorCode::DrawPending));
+        }
+        if matches!(max_rerolls, Some(max) if self.rerolls >= max) {
+            return Err(error!(ErrorCode::RerollLimitReached));
+        }
+
+        let Some(index) = self.positions.iter().position(|&position| position == pos) else {
+            return Err(error!(ErrorCode::PositionNotOnCoupon));
+        };
+        self.positions.remove(index);
+        self.pending_draws = 1;
+        self.pending_slot = Some(slot);
+        self.rerolls = self
+            .rerolls
+            .checked_add(1)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+
+        Ok(())
+    }
+
+    /// Settles the pending draws on the drawn `positions`.
+    pub fn resolve(&mut self, positions: &[u8]) {
+        self.positions.extend_from_slice(positions);
+        self.pending_draws = 0;
+        self.pending_slot = None;
+    }
+
+    /// Drops the pending draws once they can no longer be resolved, returning
+    /// how many there were.
+    pub fn forfeit_draws(&mut self) -> u8 {
+        self.pending_slot = None;
+        std::mem::take(&mut self.pending_draws)
+    }
+
+    /// Whether nothing is left to claim or draw.
+    pub fn is_spent(&self) -> bool {
+        self.positions.is_empty() && self.pending_draws == 0
+    }
+
+    /// Drops the claimed `pos`, which already left `pool.items` when it was
+    /// drawn, and closes the coupon to `receiver` once it is spent.
+    pub fn claim<'info>(
+        coupon: &mut Account<'info, CnftClaimCoupon>,
+        pos: u8,
+        receiver: AccountInfo<'info>,
+    ) -> Result<()> {
+        coupon.positions.retain(|&position| position != pos);
+        if coupon.is_spent() {
+            coupon.close(receiver)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An extra mint the pool trades in besides `HybridPoolConfig.token`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AcceptedToken {
+    pub mint: Pubkey,
+    /// Price of a single cnft, in base units of `mint`.
+    pub price: u64,
+    /// Cumulative `deposit_tokens` amount, in base units of `mint`.
+    pub deposited: u64,
+}
+
+/// Metadata an incoming cnft must match. Empty strings and `None` bounds are
+/// not checked.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MetadataRules {
+    pub symbol: String,
+    pub name_prefix: String,
+    pub uri_prefix: String,
+    pub min_seller_fee_basis_points: Option<u16>,
+    pub max_seller_fee_basis_points: Option<u16>,
+}
+
+impl MetadataRules {
+    pub fn is_valid(&self) -> bool {
+        let min = self.min_seller_fee_basis_points;
+        let max = self.max_seller_fee_basis_points;
+        let fee_ordered = match (min, max) {
+            (Some(min), Some(max)) => min <= max,
+            _ => true,
+        };
+        // seller fees are basis points, so a bound above 100% can never match
+        let fee_bounded = [min, max]
+            .into_iter()
+            .flatten()
+            .all(|bps| bps <= MAX_FEE_BPS);
+
+        self.symbol.len() <= MAX_SYMBOL_LENGTH
+            && self.name_prefix.len() <= MAX_NAME_LENGTH
+            && self.uri_prefix.len() <= MAX_URI_LENGTH
+            && fee_ordered
+            && fee_bounded
+    }
+}
+
+/// Metadata for cnfts the pool mints itself. Minted cnft number `n` is named
+/// `"{name} #{n}"` with uri `"{uri}{n}.json"`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MintTemplate {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+}
+
+impl MintTemplate {
+    pub fn is_valid(&self) -> bool {
+        !self.name.is_empty()
+            && self.name.len() + " #".len() + MAX_MINT_NUMBER_DIGITS <= MAX_NAME_LENGTH
+            && self.symbol.len() <= MAX_SYMBOL_LENGTH
+            && self.uri.len() + MAX_MINT_NUMBER_DIGITS + ".json".len() <= MAX_URI_LENGTH
+            && self.seller_fee_basis_points <= MAX_FEE_BPS
+    }
+
+    /// Metadata for cnft `number` minted by `pool` into `collection`. The pool
+    /// is a verified creator so minted cnfts can be traced back to it,
+    /// royalties go to `authority`.
+    pub fn metadata(
+        &self,
+        pool: Pubkey,
+        authority: Pubkey,
+        number: u64,
+        collection: Option<Pubkey>,
+    ) -> MetadataArgs {
+        MetadataArgs {
+            name: format!("{} #{}", self.name, number),
+            symbol: self.symbol.clone(),
+            uri: format!("{}{}.json", self.uri, number),
+            seller_fee_basis_points: self.seller_fee_basis_points,
+            primary_sale_happened: true,
+            is_mutable: false,
+            edition_nonce: None,
+            token_standard: Some(BubblegumTokenStandard::NonFungible),
+            // bubblegum verifies the collection while minting into it
+            collection: collection.map(|key| BubblegumCollection {
+                verified: false,
+                key,
+            }),
+            uses: None,
+            token_program_version: BubblegumTokenProgramVersion::Original,
+            creators: vec![
+                BubblegumCreator {
+                    address: pool,
+                    verified: true,
+                    share: 0,
+                },
+                BubblegumCreator {
+                    address: authority,
+                    verified: false,
+                    share: 100,
+                },
+            ],
+        }
+    }
 }
 
 #[account]
@@ -29,17 +276,279 @@ pub struct HybridPoolConfig {
     pub bump: u8,
     pub token: Pubkey,
 
+    /// Price of a single cnft, in base units of `token`.
     pub price: u64,
     pub collections: Vec<Pubkey>,
     pub initiated: bool,
     pub items: Vec<u8>,
+    pub accepted_tokens: Vec<AcceptedToken>,
+
+    /// Base units of `token` held by the pool vault, tracked for LP accounting.
+    pub reserve: u64,
+    /// LP share mint, `Pubkey::default()` until liquidity providers are enabled.
+    pub lp_mint: Pubkey,
+    /// Swap fee in basis points, kept in the vault for liquidity providers.
+    pub fee_bps: u16,
+    /// Cumulative `deposit_tokens` amount, in base units of `token`.
+    pub deposited: u64,
+    /// Creator addresses, one of which must have verified an incoming cnft.
+    pub creators: Vec<Pubkey>,
+    /// Merkle root over the asset ids allowed into the pool, all zeros when unset.
+    pub asset_allowlist_root: [u8; 32],
+    pub metadata_rules: MetadataRules,
+    /// Sold cnfts are burned and bought ones minted from `merkle_tree`
+    /// instead of being held by the pool.
+    pub burn_mode: bool,
+    /// Tree the pool mints into as tree creator or delegate.
+    pub merkle_tree: Pubkey,
+    pub mint_template: MintTemplate,
+    /// Number of cnfts minted from `mint_template`.
+    pub minted: u64,
+    /// Depth of `merkle_tree`, 0 unless the pool created the tree.
+    pub tree_max_depth: u32,
+    /// Buffer size of `merkle_tree`, 0 unless the pool created the tree.
+    pub tree_max_buffer_size: u32,
+    /// Re-roll fee in basis points of the price in the paying token.
+    pub reroll_fee_bps: u16,
+    /// Re-rolls allowed per coupon, unlimited when `None`.
+    pub max_rerolls: Option<u8>,
+    /// Fee for trading a cnft for a random pool cnft, in basis points of the
+    /// price in the paying token.
+    pub cnft_swap_fee_bps: u16,
+    /// Price multiplier in basis points for buying a chosen position, 0 when
+    /// only random purchases are allowed.
+    pub premium_multiplier_bps: u32,
+    /// Tier of each position, indexed by position. Positions past the end
+    /// are common.
+    pub item_tiers: Vec<Tier>,
+    /// Draw weight of a single position in each tier. All zeros draws every
+    /// position with equal odds.
+    pub tier_weights: [u16; TIER_COUNT],
+    /// Positions of each tier currently in `items`.
+    pub tier_counts: [u16; TIER_COUNT],
+    /// Positions committed to coupons but not drawn yet. They stay in
+    /// `items` until `resolve_coupon` draws them.
+    pub pending_draws: u16,
 }
 
 impl HybridPoolConfig {
-    pub const BASE_LEN: usize =
-        8 + std::mem::size_of::<Self>() + 4 + (MAX_COLLECTIONS * 32) + 4 + (MAX_ITEMS_IN_POOL * 1);
+    pub const BASE_LEN: usize = 8
+        + std::mem::size_of::<Self>()
+        + 4
+        + (MAX_COLLECTIONS * 32)
+        + 4
+        + MAX_ITEMS_IN_POOL
+        + 4
+        + (MAX_ACCEPTED_TOKENS * std::mem::size_of::<AcceptedToken>())
+        + 4
+        + (MAX_CREATORS * 32)
+        + 4
+        + MAX_SYMBOL_LENGTH
+        + 4
+        + MAX_NAME_LENGTH
+        + 4
+        + MAX_URI_LENGTH
+        + 4
+        + MAX_NAME_LENGTH
+        + 4
+        + MAX_SYMBOL_LENGTH
+        + 4
+        + MAX_URI_LENGTH
+        + 4
+        + MAX_ITEMS_IN_POOL;
     // change this number to something bigger if this exp gets bigger.
     pub const MIN_CNFTS: u8 = 5;
+
+    pub fn has_asset_allowlist(&self) -> bool {
+        self.asset_allowlist_root != [0; 32]
+    }
+
+    /// Admits the cnfts the pool mints, which carry `pool` as a verified
+    /// creator, unless the authority already set a collection or creator
+    /// allowlist. Without either every incoming cnft would be rejected.
+    pub fn admit_own_cnfts(&mut self, pool: Pubkey) {
+        if self.collections.is_empty() && self.creators.is_empty() {
+            self.creators.push(pool);
+        }
+    }
+
+    /// Whether the pool can mint cnfts instead of handing out held ones.
+    pub fn mints_on_demand(&self) -> bool {
+        // a pool can own a tree before it has a template to mint from
+        self.merkle_tree != Pubkey::default() && !self.mint_template.name.is_empty()
+    }
+
+    pub fn has_liquidity_providers(&self) -> bool {
+        self.lp_mint != Pubkey::default()
+    }
+
+    /// Value of the pool in base units of `token`, counting each cnft at `price`.
+    pub fn value(&self) -> Result<u64> {
+        total_price(self.price, self.items.len())?
+            .checked_add(self.reserve)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Updates `reserve` after `amount` of `mint` moved into the pool vault.
+    pub fn credit(&mut self, mint: &Pubkey, amount: u64) -> Result<()> {
+        if *mint == self.token {
+            self.reserve = self
+                .reserve
+                .checked_add(amount)
+                .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+        }
+        Ok(())
+    }
+
+    /// Updates `reserve` after `amount` of `mint` left the pool vault.
+    pub fn debit(&mut self, mint: &Pubkey, amount: u64) -> Result<()> {
+        if *mint == self.token {
+            self.reserve = self
+                .reserve
+                .checked_sub(amount)
+                .ok_or_else(|| error!(ErrorCode::InsufficientPoolFunds))?;
+        }
+        Ok(())
+    }
+
+    /// Adds `amount` of `mint` to the cumulative authority deposits.
+    pub fn record_deposit(&mut self, mint: &Pubkey, amount: u64) -> Result<()> {
+        let deposited = if *mint == self.token {
+            &mut self.deposited
+        } else {
+            let accepted = self
+                .accepted_tokens
+                .iter_mut()
+                .find(|accepted| accepted.mint == *mint)
+                .ok_or_else(|| error!(ErrorCode::UnsupportedToken))?;
+            &mut accepted.deposited
+        };
+
+        *deposited = deposited
+            .checked_add(amount)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+        Ok(())
+    }
+
+    pub fn accepts(&self, mint: &Pubkey) -> bool {
+        self.price_for(mint).is_ok()
+    }
+
+    /// Price of a single cnft when paying in `mint`. Payments in `NATIVE_MINT`
+    /// go through the `sol_vault` PDA instead of a token vault.
+    pub fn price_for(&self, mint: &Pubkey) -> Result<u64> {
+        if *mint == self.token {
+            return Ok(self.price);
+        }
+
+        self.accepted_tokens
+            .iter()
+            .find(|accepted| accepted.mint == *mint)
+            .map(|accepted| accepted.price)
+            .ok_or_else(|| error!(ErrorCode::UnsupportedToken))
+    }
+
+    /// Positions in `items` no pending draw is counting on.
+    pub fn undrawn_items(&self) -> usize {
+        self.items.len().saturating_sub(self.pending_draws as usize)
+    }
+
+    /// Holds back `count` positions for draws resolved later.
+    pub fn commit_draws(&mut self, count: u8) -> Result<()> {
+        if count as usize > self.undrawn_items() {
+            return Err(error!(ErrorCode::NoCnftsInPool));
+        }
+        self.pending_draws = self
+            .pending_draws
+            .checked_add(count as u16)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+
+        Ok(())
+    }
+
+    /// Lets go of `count` positions held back by `commit_draws`, once they
+    /// are drawn or forfeited.
+    pub fn release_draws(&mut self, count: u8) {
+        self.pending_draws = self.pending_draws.saturating_sub(count as u16);
+    }
+
+    pub fn tier_of(&self, pos: u8) -> Tier {
+        self.item_tiers
+            .get(pos as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Adds a freshly deposited nft at `pos`, which starts out common.
+    pub fn add_item(&mut self, pos: u8) {
+        self.store_tier(pos, Tier::Common);
+        self.return_item(pos);
+    }
+
+    /// Puts a position back in the pool, keeping its tier.
+    pub fn return_item(&mut self, pos: u8) {
+        let count = &mut self.tier_counts[self.tier_of(pos) as usize];
+        *count = count.saturating_add(1);
+        self.items.push(pos);
+    }
+
+    /// Removes the position at `index` of `items`.
+    pub fn take_item(&mut self, index: usize) -> u8 {
+        let pos = self.items.swap_remove(index);
+        let count = &mut self.tier_counts[self.tier_of(pos) as usize];
+        *count = count.saturating_sub(1);
+        pos
+    }
+
+    pub fn remove_item(&mut self, pos: u8) -> Result<()> {
+        let index = self
+            .items
+            .iter()
+            .position(|&item| item == pos)
+            .ok_or_else(|| error!(ErrorCode::PositionNotInPool))?;
+        self.take_item(index);
+        Ok(())
+    }
+
+    /// Moves the position at `pos`, which must be in the pool, to `tier`.
+    pub fn set_tier(&mut self, pos: u8, tier: Tier) -> Result<()> {
+        self.remove_item(pos)?;
+        self.store_tier(pos, tier);
+        self.return_item(pos);
+        Ok(())
+    }
+
+    fn store_tier(&mut self, pos: u8, tier: Tier) {
+        let index = pos as usize;
+        if index >= self.item_tiers.len() {
+            if tier == Tier::Common {
+                return;
+            }
+            self.item_tiers.resize(index + 1, Tier::Common);
+        }
+        self.item_tiers[index] = tier;
+    }
+
+    /// Index into `items` picked by `seed`, each position weighted by its
+    /// tier. `None` when the pool holds nothing.
+    pub fn pick_item(&self, seed: u64) -> Option<usize> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        let weight = |pos: u8| self.tier_weights[self.tier_of(pos) as usize] as u64;
+        let total: u64 = self.items.iter().map(|&pos| weight(pos)).sum();
+        if total == 0 {
+            return Some((seed % self.items.len() as u64) as usize);
+        }
+
+        let mut target = seed % total;
+        self.items.iter().position(|&pos| {
+            let hit = target < weight(pos);
+            target = target.saturating_sub(weight(pos));
+            hit
+        })
+    }
 }
 
 // things needed next would be instructions like
@@ -47,6 +556,50 @@ impl HybridPoolConfig {
 // 2. swap from pool
 // 3. next will think about it.
 
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AnchorMetadataArgs {
+    /// The name of the asset
+    pub name: String,
+    /// The symbol for the asset
+    pub symbol: String,
+    /// URI pointing to JSON representing the asset
+    pub uri: String,
+    /// Royalty basis points that goes to creators in secondary sales (0-10000)
+    pub seller_fee_basis_points: u16,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    /// nonce for easy calculation of editions, if present
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<TokenStandard>,
+    /// Collection
+    pub collection: Option<Collection>,
+    /// Uses
+    pub uses: Option<Uses>,
+    pub token_program_version: TokenProgramVersion,
+    pub creators: Vec<Creator>,
+}
+
+impl TryFrom<AnchorMetadataArgs> for MetadataArgs {
+    type Error = Error;
+
+    fn try_from(args: AnchorMetadataArgs) -> Result<Self> {
+        Ok(MetadataArgs {
+            name: args.name,
+            symbol: args.symbol,
+            uri: args.uri,
+            seller_fee_basis_points: args.seller_fee_basis_points,
+            primary_sale_happened: args.primary_sale_happened,
+            is_mutable: args.is_mutable,
+            edition_nonce: args.edition_nonce,
+            token_standard: args.token_standard.map(|ts| ts.convert()),
+            collection: args.collection.map(|c| c.convert()),
+            uses: args.uses.map(|u| u.convert()),
+            token_program_version: args.token_program_version.convert(),
+            creators: args.creators.iter().map(|c| c.convert()).collect(),
+        })
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub enum TokenStandard {
     NonFungible,
@@ -0,0 +1,34 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as Token2022Mint;
+use anchor_spl::token_interface::Mint;
+
+/// Fee withheld when `amount` of `mint` is transferred in `epoch`, zero for
+/// mints without the transfer fee extension.
+pub fn transfer_fee(mint: &AccountInfo, epoch: u64, amount: u64) -> Result<u64> {
+    if *mint.owner != anchor_spl::token_2022::ID {
+        return Ok(0);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_with_extensions = StateWithExtensions::<Token2022Mint>::unpack(&mint_data)?;
+    match mint_with_extensions.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow)),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Part of `amount` that reaches the receiver once the mint's transfer fee
+/// is withheld.
+pub fn net_amount(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let fee = transfer_fee(&mint.to_account_info(), Clock::get()?.epoch, amount)?;
+    amount
+        .checked_sub(fee)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
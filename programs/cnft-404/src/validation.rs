@@ -0,0 +1,164 @@
+use crate::allowlist::verify_proof;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::mpl_token_metadata::accounts::Metadata;
+use mpl_bubblegum::hash::{hash_creators, hash_metadata};
+use mpl_bubblegum::types::{Collection, Creator, MetadataArgs, TokenProgramVersion};
+use mpl_core::accounts::BaseAssetV1;
+use mpl_core::types::UpdateAuthority;
+
+/// Checks `metadata` is the leaf being moved and that the pool admits it.
+/// Both hashes are recomputed, since the caller supplies them alongside the
+/// metadata they are meant to commit to.
+pub fn verify_leaf(
+    metadata: &MetadataArgs,
+    data_hash: &[u8; 32],
+    creator_hash: &[u8; 32],
+    pool: &HybridPoolConfig,
+) -> Result<()> {
+    if hash_metadata(metadata)? != *data_hash {
+        return Err(error!(ErrorCode::InvalidDataHash));
+    }
+
+    if hash_creators(&metadata.creators) != *creator_hash {
+        return Err(error!(ErrorCode::InvalidCreatorHash));
+    }
+
+    verify_admission(metadata, pool)
+}
+
+/// Checks the cnft against the pool admission rules. A pool with both a
+/// collection and a creator allowlist requires the cnft to pass both.
+pub fn verify_admission(metadata: &MetadataArgs, pool: &HybridPoolConfig) -> Result<()> {
+    if pool.creators.is_empty() || !pool.collections.is_empty() {
+        verify_collection(metadata, pool)?;
+    }
+
+    if !pool.creators.is_empty() {
+        verify_creators(metadata, pool)?;
+    }
+
+    verify_metadata_rules(metadata, &pool.metadata_rules)
+}
+
+/// Checks the cnft belongs to a verified collection on the pool allowlist.
+pub fn verify_collection(metadata: &MetadataArgs, pool: &HybridPoolConfig) -> Result<()> {
+    let Some(collection) = &metadata.collection else {
+        return Err(error!(ErrorCode::InvalidCollection));
+    };
+
+    if !collection.verified {
+        return Err(error!(ErrorCode::UnverifiedCollection));
+    }
+
+    if !pool.collections.contains(&collection.key) {
+        return Err(error!(ErrorCode::InvalidCollection));
+    }
+
+    Ok(())
+}
+
+/// Checks one of the pool creators verified the cnft.
+pub fn verify_creators(metadata: &MetadataArgs, pool: &HybridPoolConfig) -> Result<()> {
+    let verified = metadata
+        .creators
+        .iter()
+        .any(|creator| creator.verified && pool.creators.contains(&creator.address));
+    if !verified {
+        return Err(error!(ErrorCode::InvalidCreator));
+    }
+
+    Ok(())
+}
+
+/// Checks a Core asset against the pool admission rules. Core assets are
+/// admitted by collection and the name and uri rules, and the asset allowlist
+/// applies to them by asset id. They carry no symbol or verified creators, and
+/// their royalty lives in a plugin this check does not read, so a pool with a
+/// symbol rule, a creator allowlist or a seller fee bound rejects them.
+pub fn verify_core_admission(asset: &BaseAssetV1, pool: &HybridPoolConfig) -> Result<()> {
+    let rules = &pool.metadata_rules;
+    if !rules.symbol.is_empty()
+        || !pool.creators.is_empty()
+        || rules.min_seller_fee_basis_points.is_some()
+        || rules.max_seller_fee_basis_points.is_some()
+    {
+        return Err(error!(ErrorCode::UnsupportedCoreRule));
+    }
+
+    let UpdateAuthority::Collection(collection) = asset.update_authority else {
+        return Err(error!(ErrorCode::InvalidCollection));
+    };
+
+    if !pool.collections.contains(&collection) {
+        return Err(error!(ErrorCode::InvalidCollection));
+    }
+
+    if !asset.name.starts_with(&rules.name_prefix) || !asset.uri.starts_with(&rules.uri_prefix) {
+        return Err(error!(ErrorCode::MetadataRuleMismatch));
+    }
+
+    Ok(())
+}
+
+/// Checks a Token Metadata nft against the same rules as a cnft. Token
+/// Metadata pads name, symbol and uri with zero bytes, which are trimmed first.
+pub fn verify_legacy_admission(metadata: &Metadata, pool: &HybridPoolConfig) -> Result<()> {
+    let creators = metadata.creators.as_deref().unwrap_or_default();
+    let args = MetadataArgs {
+        name: metadata.name.trim_end_matches('\0').to_string(),
+        symbol: metadata.symbol.trim_end_matches('\0').to_string(),
+        uri: metadata.uri.trim_end_matches('\0').to_string(),
+        seller_fee_basis_points: metadata.seller_fee_basis_points,
+        primary_sale_happened: metadata.primary_sale_happened,
+        is_mutable: metadata.is_mutable,
+        edition_nonce: metadata.edition_nonce,
+        token_standard: None,
+        collection: metadata.collection.as_ref().map(|collection| Collection {
+            verified: collection.verified,
+            key: collection.key,
+        }),
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: creators
+            .iter()
+            .map(|creator| Creator {
+                address: creator.address,
+                verified: creator.verified,
+                share: creator.share,
+            })
+            .collect(),
+    };
+
+    verify_admission(&args, pool)
+}
+
+/// Checks the cnft symbol, name, uri and royalty against the pool rules.
+pub fn verify_metadata_rules(metadata: &MetadataArgs, rules: &MetadataRules) -> Result<()> {
+    let fee = metadata.seller_fee_basis_points;
+    let matches = (rules.symbol.is_empty() || metadata.symbol == rules.symbol)
+        && metadata.name.starts_with(&rules.name_prefix)
+        && metadata.uri.starts_with(&rules.uri_prefix)
+        && !matches!(rules.min_seller_fee_basis_points, Some(min) if fee < min)
+        && !matches!(rules.max_seller_fee_basis_points, Some(max) if fee > max);
+
+    if !matches {
+        return Err(error!(ErrorCode::MetadataRuleMismatch));
+    }
+
+    Ok(())
+}
+
+/// Checks `asset_id` is on the pool asset allowlist, when the pool has one.
+pub fn verify_allowlisted_asset(
+    asset_id: &Pubkey,
+    proof: &[[u8; 32]],
+    pool: &HybridPoolConfig,
+) -> Result<()> {
+    if pool.has_asset_allowlist() && !verify_proof(&pool.asset_allowlist_root, asset_id, proof) {
+        return Err(error!(ErrorCode::AssetNotAllowlisted));
+    }
+
+    Ok(())
+}
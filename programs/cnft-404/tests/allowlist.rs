@@ -0,0 +1,49 @@
+use anchor_lang::prelude::Pubkey;
+use drip_rewards::allowlist::{verify_proof, AssetAllowlist};
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::validation::verify_allowlisted_asset;
+use drip_rewards::HybridPoolConfig;
+
+#[test]
+fn every_listed_asset_has_a_valid_proof() {
+    for size in 1..=17 {
+        let assets = (0..size).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        let allowlist = AssetAllowlist::new(&assets);
+        let root = allowlist.root();
+
+        for asset in &assets {
+            let proof = allowlist.proof(asset).unwrap();
+            assert!(verify_proof(&root, asset, &proof), "size {size}");
+        }
+    }
+}
+
+#[test]
+fn unlisted_asset_is_rejected() {
+    let assets = (0..5).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let allowlist = AssetAllowlist::new(&assets);
+    let outsider = Pubkey::new_unique();
+    let borrowed_proof = allowlist.proof(&assets[0]).unwrap();
+
+    assert!(allowlist.proof(&outsider).is_none());
+    assert!(!verify_proof(&allowlist.root(), &outsider, &borrowed_proof));
+}
+
+#[test]
+fn pool_only_checks_proofs_when_a_root_is_set() {
+    let assets = (0..3).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let allowlist = AssetAllowlist::new(&assets);
+    let outsider = Pubkey::new_unique();
+
+    let open_pool = HybridPoolConfig::default();
+    assert!(verify_allowlisted_asset(&outsider, &[], &open_pool).is_ok());
+
+    let curated_pool = HybridPoolConfig {
+        asset_allowlist_root: allowlist.root(),
+        ..Default::default()
+    };
+    let proof = allowlist.proof(&assets[1]).unwrap();
+    assert!(verify_allowlisted_asset(&assets[1], &proof, &curated_pool).is_ok());
+    let err = verify_allowlisted_asset(&outsider, &proof, &curated_pool).unwrap_err();
+    assert_eq!(err, ErrorCode::AssetNotAllowlisted.into());
+}
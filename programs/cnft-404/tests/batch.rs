@@ -0,0 +1,211 @@
+use anchor_lang::prelude::{AccountInfo, AccountMeta, Pubkey};
+use anchor_lang::solana_program::address_lookup_table::AddressLookupTableAccount;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::message::{v0, VersionedMessage};
+use anchor_lang::{AnchorSerialize, InstructionData, ToAccountMetas};
+use drip_rewards::accounts::BubblegumAccounts;
+use drip_rewards::accounts::LpAccounts;
+use drip_rewards::batch::{
+    chunk_deposits, split_leaf_accounts, verify_nft_store_address, BatchLimits,
+    DEPOSIT_CNFTS_ACCOUNTS, MAX_ACCOUNT_LOCKS, PACKET_DATA_SIZE,
+};
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::{AnchorMetadataArgs, DepositCnftArgs, TokenProgramVersion};
+
+#[test]
+fn leaf_accounts_split_by_proof_length() {
+    let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+    let owner = Pubkey::new_unique();
+    let mut lamports = vec![0u64; keys.len()];
+    let mut data = vec![vec![]; keys.len()];
+    let accounts: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .map(|((key, lamports), data)| {
+            AccountInfo::new(key, false, false, lamports, data, &owner, false, 0)
+        })
+        .collect();
+
+    let leaves = split_leaf_accounts(&accounts, &[2, 1]).unwrap();
+    assert_eq!(leaves.len(), 2);
+    assert_eq!(*leaves[0].nft_store.key, keys[0]);
+    assert_eq!(leaves[0].proof.len(), 2);
+    assert_eq!(*leaves[1].nft_store.key, keys[3]);
+    assert_eq!(*leaves[1].proof[0].key, keys[4]);
+
+    for proof_lengths in [&[2, 2][..], &[1, 1], &[3]] {
+        let err = split_leaf_accounts(&accounts, proof_lengths).err().unwrap();
+        assert_eq!(err, ErrorCode::InvalidLeafAccounts.into());
+    }
+}
+
+#[test]
+fn nft_store_must_be_the_position_pda() {
+    let pool = Pubkey::new_unique();
+    let (nft_store, bump) =
+        Pubkey::find_program_address(&[b"cnft", pool.as_ref(), &[7]], &drip_rewards::ID);
+
+    assert!(verify_nft_store_address(&nft_store, &pool, 7, bump).is_ok());
+    let err = verify_nft_store_address(&nft_store, &pool, 8, bump).unwrap_err();
+    assert_eq!(err, ErrorCode::InvalidNftStore.into());
+}
+
+fn deposit(pos: u8) -> DepositCnftArgs {
+    deposit_with_uri(pos, format!("https://example.com/{pos}.json"))
+}
+
+fn deposit_with_uri(pos: u8, uri: String) -> DepositCnftArgs {
+    DepositCnftArgs {
+        root: [0; 32],
+        data_hash: [0; 32],
+        creator_hash: [0; 32],
+        nonce: pos as u64,
+        index: pos as u32,
+        pos,
+        metadata: AnchorMetadataArgs {
+            name: format!("Drip #{pos}"),
+            symbol: "DRIP".to_string(),
+            uri,
+            seller_fee_basis_points: 500,
+            primary_sale_happened: false,
+            is_mutable: true,
+            edition_nonce: None,
+            token_standard: None,
+            collection: None,
+            uses: None,
+            token_program_version: TokenProgramVersion::Original,
+            creators: vec![],
+        },
+        asset_proof: vec![],
+    }
+}
+
+#[test]
+fn deposits_are_chunked_within_every_limit() {
+    let leaves: Vec<DepositCnftArgs> = (0..10).map(deposit).collect();
+    let proof_lengths = [14; 10];
+
+    // accounts bind first: 3 leaves of 15 accounts fit in 48
+    let chunks = chunk_deposits(&leaves, &proof_lengths, &BatchLimits::default());
+    assert_eq!(chunks, vec![0..3, 3..6, 6..9, 9..10]);
+
+    let limits = BatchLimits {
+        max_leaves: 4,
+        max_accounts: 256,
+        max_data_len: 10_000,
+    };
+    let chunks = chunk_deposits(&leaves, &proof_lengths, &limits);
+    assert_eq!(chunks, vec![0..4, 4..8, 8..10]);
+
+    let limits = BatchLimits {
+        max_data_len: 1,
+        ..limits
+    };
+    assert_eq!(
+        chunk_deposits(&leaves[..2], &proof_lengths, &limits),
+        vec![0..1, 1..2]
+    );
+    assert!(chunk_deposits(&[], &[], &limits).is_empty());
+}
+
+/// Serialized size and locked accounts of a signed `deposit_cnfts`
+/// transaction, every account but the signer and the program loaded from
+/// one lookup table.
+fn deposit_transaction(leaves: Vec<DepositCnftArgs>, proof_lengths: Vec<u8>) -> (usize, usize) {
+    let key = Pubkey::new_unique;
+    let authority = key();
+    let accounts = drip_rewards::accounts::DepositCnfts {
+        authority,
+        pool: key(),
+        bubblegum: BubblegumAccounts {
+            tree_authority: Some(key()),
+            merkle_tree: Some(key()),
+            log_wrapper: Some(key()),
+            bubblegum_program: Some(key()),
+            compression_program: Some(key()),
+        },
+        lp: LpAccounts {
+            lp_mint: Some(key()),
+            user_lp_account: Some(key()),
+            lp_token_program: Some(key()),
+            associated_token_program: Some(key()),
+        },
+        system_program: key(),
+    };
+    let mut metas = accounts.to_account_metas(None);
+    assert_eq!(metas.len(), DEPOSIT_CNFTS_ACCOUNTS);
+    for &proof_length in &proof_lengths {
+        metas.push(AccountMeta::new(key(), false));
+        metas.extend((0..proof_length).map(|_| AccountMeta::new_readonly(key(), false)));
+    }
+
+    let table = AddressLookupTableAccount {
+        key: key(),
+        addresses: metas.iter().skip(1).map(|meta| meta.pubkey).collect(),
+    };
+    let instruction = Instruction {
+        program_id: drip_rewards::ID,
+        accounts: metas,
+        data: drip_rewards::instruction::DepositCnfts {
+            leaves,
+            proof_lengths,
+        }
+        .data(),
+    };
+    let message =
+        v0::Message::try_compile(&authority, &[instruction], &[table], Hash::default()).unwrap();
+    let locked = message.account_keys.len()
+        + message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum::<usize>();
+    let signature_len = 1 + 64;
+
+    (
+        signature_len + VersionedMessage::V0(message).serialize().len(),
+        locked,
+    )
+}
+
+#[test]
+fn full_default_chunks_fit_one_transaction() {
+    let limits = BatchLimits::default();
+
+    // long uris fill the args, long proofs the accounts
+    for (uri_len, proof_length) in [(200, 16), (200, 0), (120, 5), (20, 24)] {
+        let uri = "u".repeat(uri_len);
+        let leaves: Vec<DepositCnftArgs> = (0..20)
+            .map(|pos| deposit_with_uri(pos, uri.clone()))
+            .collect();
+        let proof_lengths = vec![proof_length; leaves.len()];
+
+        for chunk in chunk_deposits(&leaves, &proof_lengths, &limits) {
+            let batch = (chunk.clone())
+                .map(|pos| deposit_with_uri(pos as u8, uri.clone()))
+                .collect();
+            let (len, locked) = deposit_transaction(batch, proof_lengths[chunk].to_vec());
+            assert!(len <= PACKET_DATA_SIZE, "{len} bytes");
+            assert!(locked <= MAX_ACCOUNT_LOCKS, "{locked} accounts");
+        }
+    }
+
+    // three leaves with 16 node proofs take every account, and their uris
+    // are sized to take every byte of args
+    let leaf_len = deposit_with_uri(0, String::new())
+        .try_to_vec()
+        .unwrap()
+        .len()
+        + 1;
+    let uri = "u".repeat(limits.max_data_len / 3 - leaf_len);
+    let leaves: Vec<DepositCnftArgs> = (0..3)
+        .map(|pos| deposit_with_uri(pos, uri.clone()))
+        .collect();
+    let proof_lengths = vec![16; 3];
+    assert_eq!(chunk_deposits(&leaves, &proof_lengths, &limits), vec![0..3]);
+    let (len, locked) = deposit_transaction(leaves, proof_lengths);
+    assert_eq!(locked, MAX_ACCOUNT_LOCKS);
+    assert_eq!(len, PACKET_DATA_SIZE);
+}
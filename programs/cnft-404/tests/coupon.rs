@@ -0,0 +1,161 @@
+use anchor_lang::prelude::Pubkey;
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::random::{resolve_positions, slot_hash_after, SlotHashAfter};
+use drip_rewards::{CnftClaimCoupon, HybridPoolConfig, MAX_COUPON_POSITIONS};
+
+fn coupon(positions: &[u8]) -> CnftClaimCoupon {
+    CnftClaimCoupon {
+        positions: positions.to_vec(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn reroll_waits_for_a_later_slot_and_counts() {
+    let mut coupon = coupon(&[3, 7]);
+
+    coupon.reroll(3, 100, None).unwrap();
+    assert_eq!(coupon.positions, vec![7]);
+    assert_eq!(coupon.pending_slot, Some(100));
+    assert_eq!(coupon.pending_draws, 1);
+    assert_eq!(coupon.rerolls, 1);
+
+    let err = coupon.reroll(7, 101, None).unwrap_err();
+    assert_eq!(err, ErrorCode::DrawPending.into());
+
+    coupon.resolve(&[9]);
+    assert_eq!(coupon.positions, vec![7, 9]);
+    assert_eq!(coupon.pending_slot, None);
+    assert_eq!(coupon.pending_draws, 0);
+
+    let err = coupon.reroll(3, 102, None).unwrap_err();
+    assert_eq!(err, ErrorCode::PositionNotOnCoupon.into());
+}
+
+#[test]
+fn reroll_stops_at_the_pool_cap() {
+    let mut coupon = coupon(&[3]);
+
+    for (slot, pos, position) in [(100, 3, 4), (200, 4, 5)] {
+        coupon.reroll(pos, slot, Some(2)).unwrap();
+        coupon.resolve(&[position]);
+    }
+    let err = coupon.reroll(5, 300, Some(2)).unwrap_err();
+    assert_eq!(err, ErrorCode::RerollLimitReached.into());
+    assert_eq!(coupon.positions, vec![5]);
+}
+
+#[test]
+fn draws_commit_to_one_pool_and_fill_the_coupon() {
+    let pool = Pubkey::new_unique();
+    let mut coupon = CnftClaimCoupon::default();
+
+    coupon.commit_draws(pool, 254, 4, 100).unwrap();
+    assert_eq!((coupon.pool, coupon.bump), (pool, 254));
+    assert_eq!(coupon.pending_draws, 4);
+    let err = coupon.commit_draws(pool, 254, 1, 100).unwrap_err();
+    assert_eq!(err, ErrorCode::DrawPending.into());
+
+    coupon.resolve(&[1, 2, 3, 4]);
+    let err = coupon
+        .commit_draws(Pubkey::new_unique(), 254, 1, 200)
+        .unwrap_err();
+    assert_eq!(err, ErrorCode::CouponForAnotherPool.into());
+    let err = coupon
+        .commit_draws(pool, 254, (MAX_COUPON_POSITIONS - 3) as u8, 200)
+        .unwrap_err();
+    assert_eq!(err, ErrorCode::TooManyCouponPositions.into());
+
+    // forfeited draws leave the drawn positions alone
+    coupon.commit_draws(pool, 254, 2, 200).unwrap();
+    assert_eq!(coupon.forfeit_draws(), 2);
+    assert_eq!(coupon.positions, vec![1, 2, 3, 4]);
+    assert!(!coupon.is_spent());
+}
+
+#[test]
+fn pending_draws_hold_back_pool_positions() {
+    let mut pool = HybridPoolConfig::default();
+    for pos in 0..3 {
+        pool.add_item(pos);
+    }
+
+    pool.commit_draws(2).unwrap();
+    assert_eq!(pool.undrawn_items(), 1);
+    let err = pool.commit_draws(2).unwrap_err();
+    assert_eq!(err, ErrorCode::NoCnftsInPool.into());
+
+    let positions = resolve_positions(&mut pool, 2, &hash(101), &[]).unwrap();
+    pool.release_draws(2);
+    assert_eq!(pool.items.len(), 1);
+    assert_eq!(pool.undrawn_items(), 1);
+    assert!(positions.iter().all(|pos| !pool.items.contains(pos)));
+    assert_ne!(positions[0], positions[1]);
+}
+
+/// SlotHashes sysvar data for `slots`, newest first, each hashed to its own
+/// slot number.
+fn slot_hashes(slots: &[u64]) -> Vec<u8> {
+    let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+    for slot in slots {
+        data.extend_from_slice(&slot.to_le_bytes());
+        data.extend_from_slice(&hash(*slot));
+    }
+    data
+}
+
+fn hash(slot: u64) -> [u8; 32] {
+    [slot as u8; 32]
+}
+
+#[test]
+fn draw_resolves_from_the_first_slot_after_the_commit() {
+    // slot 102 was skipped
+    let data = slot_hashes(&[104, 103, 101, 100, 99]);
+
+    assert_eq!(slot_hash_after(&data, 100), SlotHashAfter::Ready(hash(101)));
+    assert_eq!(slot_hash_after(&data, 101), SlotHashAfter::Ready(hash(103)));
+    assert_eq!(slot_hash_after(&data, 102), SlotHashAfter::Ready(hash(103)));
+}
+
+#[test]
+fn draw_waits_until_a_later_slot_hash_exists() {
+    let data = slot_hashes(&[104, 103]);
+
+    assert_eq!(slot_hash_after(&data, 104), SlotHashAfter::Pending);
+    assert_eq!(slot_hash_after(&data, 105), SlotHashAfter::Pending);
+    assert_eq!(
+        slot_hash_after(&slot_hashes(&[]), 1),
+        SlotHashAfter::Pending
+    );
+}
+
+#[test]
+fn draw_expires_once_the_commit_slot_leaves_the_sysvar() {
+    let data = slot_hashes(&[104, 103]);
+
+    assert_eq!(slot_hash_after(&data, 102), SlotHashAfter::Expired);
+}
+
+#[test]
+fn resolved_positions_depend_on_the_holder() {
+    let mut pool = HybridPoolConfig::default();
+    for pos in 1..=250 {
+        pool.add_item(pos);
+    }
+    let positions = (0..8u8)
+        .map(|holder| resolve_positions(&mut pool.clone(), 1, &hash(101), &[&[holder]]).unwrap()[0])
+        .collect::<Vec<_>>();
+
+    assert!(positions
+        .iter()
+        .all(|position| pool.items.contains(position)));
+    assert!(positions.iter().any(|position| *position != positions[0]));
+    assert_eq!(
+        vec![positions[0]],
+        resolve_positions(&mut pool.clone(), 1, &hash(101), &[&[0]]).unwrap()
+    );
+
+    let err = resolve_positions(&mut pool, 251, &hash(101), &[]).unwrap_err();
+    assert_eq!(err, ErrorCode::NoCnftsInPool.into());
+}
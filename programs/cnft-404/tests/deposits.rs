@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::{AcceptedToken, HybridPoolConfig};
+
+#[test]
+fn deposits_accumulate_per_token() {
+    let token = Pubkey::new_unique();
+    let partner = Pubkey::new_unique();
+    let mut pool = HybridPoolConfig {
+        token,
+        accepted_tokens: vec![AcceptedToken {
+            mint: partner,
+            price: 7_000,
+            deposited: 0,
+        }],
+        ..HybridPoolConfig::default()
+    };
+
+    pool.record_deposit(&token, 10_000).unwrap();
+    pool.record_deposit(&token, 2_500).unwrap();
+    pool.record_deposit(&partner, 700).unwrap();
+
+    assert_eq!(pool.deposited, 12_500);
+    assert_eq!(pool.accepted_tokens[0].deposited, 700);
+}
+
+#[test]
+fn deposits_reject_unknown_mints_and_overflow() {
+    let token = Pubkey::new_unique();
+    let mut pool = HybridPoolConfig {
+        token,
+        deposited: u64::MAX,
+        ..HybridPoolConfig::default()
+    };
+
+    assert_eq!(
+        pool.record_deposit(&Pubkey::new_unique(), 1).unwrap_err(),
+        ErrorCode::UnsupportedToken.into()
+    );
+    assert_eq!(
+        pool.record_deposit(&token, 1).unwrap_err(),
+        ErrorCode::MathOverflow.into()
+    );
+    assert_eq!(pool.deposited, u64::MAX);
+}
@@ -0,0 +1,118 @@
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::math::{
+    fee_amount, premium_price, pro_rata, sale_payout, shares_for_deposit, to_base_units,
+    total_price,
+};
+use drip_rewards::{BASE_MULTIPLIER_BPS, MAX_FEE_BPS, MAX_ITEMS_IN_POOL};
+use proptest::prelude::*;
+
+fn is_overflow(err: anchor_lang::error::Error) -> bool {
+    err == ErrorCode::MathOverflow.into()
+}
+
+proptest! {
+    #[test]
+    fn base_units_match_wide_arithmetic(amount in any::<u64>(), decimals in 0u8..=u8::MAX) {
+        let expected = 10u128
+            .checked_pow(decimals as u32)
+            .and_then(|factor| (amount as u128).checked_mul(factor))
+            .filter(|value| *value <= u64::MAX as u128);
+
+        match to_base_units(amount, decimals) {
+            Ok(value) => prop_assert_eq!(Some(value as u128), expected),
+            Err(err) => {
+                prop_assert!(expected.is_none());
+                prop_assert!(is_overflow(err));
+            }
+        }
+    }
+
+    #[test]
+    fn base_units_fit_for_realistic_mints(amount in 0u64..=1_000_000_000, decimals in 0u8..=9) {
+        let value = to_base_units(amount, decimals).unwrap();
+        prop_assert_eq!(value / 10u64.pow(decimals as u32), amount);
+    }
+
+    #[test]
+    fn total_price_matches_wide_arithmetic(price in any::<u64>(), count in 0usize..=MAX_ITEMS_IN_POOL) {
+        let expected = price as u128 * count as u128;
+
+        match total_price(price, count) {
+            Ok(value) => prop_assert_eq!(value as u128, expected),
+            Err(err) => {
+                prop_assert!(expected > u64::MAX as u128);
+                prop_assert!(is_overflow(err));
+            }
+        }
+    }
+
+    #[test]
+    fn fee_never_exceeds_amount(amount in any::<u64>(), fee_bps in 0u16..=MAX_FEE_BPS) {
+        let fee = fee_amount(amount, fee_bps).unwrap();
+        prop_assert!(fee <= amount);
+        prop_assert!(fee as u128 * 10_000 >= amount as u128 * fee_bps as u128);
+    }
+
+    #[test]
+    fn sale_payout_never_exceeds_the_price(price in 0u64..=u64::MAX / 256, count in 0usize..=MAX_ITEMS_IN_POOL, fee_bps in 0u16..=MAX_FEE_BPS) {
+        let payout = sale_payout(price, count, fee_bps, 0).unwrap();
+        let total = total_price(price, count).unwrap();
+        prop_assert_eq!(payout, total - fee_amount(total, fee_bps).unwrap());
+        prop_assert!(sale_payout(price, count, fee_bps, payout).is_ok());
+    }
+
+    #[test]
+    fn premium_never_undercuts_price(price in 0u64..=u64::MAX / 10, multiplier_bps in BASE_MULTIPLIER_BPS..=100_000) {
+        let premium = premium_price(price, multiplier_bps).unwrap();
+        prop_assert!(premium >= price);
+        prop_assert!(premium as u128 * 10_000 >= price as u128 * multiplier_bps as u128);
+    }
+
+    #[test]
+    fn withdrawing_new_shares_never_returns_more_than_deposited(
+        value in 1u64..=u64::MAX / 2,
+        pool_value in 1u64..=u64::MAX / 2,
+        supply in 1u64..=u64::MAX / 2,
+    ) {
+        let shares = shares_for_deposit(value, pool_value, supply);
+        prop_assume!(shares.is_ok());
+        let shares = shares.unwrap();
+        let new_supply = supply.checked_add(shares);
+        prop_assume!(new_supply.is_some());
+
+        let redeemed = pro_rata(pool_value + value, shares, new_supply.unwrap()).unwrap();
+        prop_assert!(redeemed <= value);
+    }
+
+    #[test]
+    fn total_price_of_full_pool_at_nine_decimals(whole_tokens in 0u64..=10_000_000) {
+        let price = to_base_units(whole_tokens, 9).unwrap();
+        let total = total_price(price, MAX_ITEMS_IN_POOL).unwrap();
+        prop_assert_eq!(total, price * MAX_ITEMS_IN_POOL as u64);
+    }
+}
+
+#[test]
+fn extreme_values_overflow() {
+    assert!(is_overflow(to_base_units(u64::MAX, 1).unwrap_err()));
+    assert!(is_overflow(to_base_units(1, 20).unwrap_err()));
+    assert!(is_overflow(total_price(u64::MAX, 2).unwrap_err()));
+    assert_eq!(to_base_units(u64::MAX, 0).unwrap(), u64::MAX);
+    assert_eq!(to_base_units(1, 19).unwrap(), 10u64.pow(19));
+    assert_eq!(total_price(u64::MAX, 1).unwrap(), u64::MAX);
+    assert!(is_overflow(pro_rata(1, 1, 0).unwrap_err()));
+    assert!(is_overflow(pro_rata(u64::MAX, u64::MAX, 1).unwrap_err()));
+    assert_eq!(shares_for_deposit(500, 0, 0).unwrap(), 500);
+    assert!(is_overflow(premium_price(u64::MAX, 20_000).unwrap_err()));
+}
+
+#[test]
+fn sale_payout_is_held_to_the_minimum() {
+    // 3 items at 1000 less 2.5%, rounded in the pool's favour
+    assert_eq!(sale_payout(1000, 3, 250, 0).unwrap(), 2925);
+    assert_eq!(sale_payout(1000, 3, 250, 2925).unwrap(), 2925);
+    let err = sale_payout(1000, 3, 250, 2926).unwrap_err();
+    assert_eq!(err, ErrorCode::SlippageExceeded.into());
+    assert_eq!(sale_payout(1001, 1, 1, 0).unwrap(), 1000);
+    assert!(is_overflow(sale_payout(u64::MAX, 2, 0, 0).unwrap_err()));
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::Pubkey;
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::validation::verify_admission;
+use drip_rewards::{HybridPoolConfig, MintTemplate, MAX_NAME_LENGTH, MAX_URI_LENGTH};
+
+fn template() -> MintTemplate {
+    MintTemplate {
+        name: "Drip".to_string(),
+        symbol: "DRIP".to_string(),
+        uri: "https://example.com/drip/".to_string(),
+        seller_fee_basis_points: 500,
+    }
+}
+
+#[test]
+fn minted_metadata_is_verified_by_the_pool() {
+    let pool = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let metadata = template().metadata(pool, authority, 1, None);
+
+    let verified = metadata
+        .creators
+        .iter()
+        .filter(|creator| creator.verified)
+        .map(|creator| creator.address)
+        .collect::<Vec<_>>();
+    assert_eq!(verified, vec![pool]);
+
+    let shares = metadata
+        .creators
+        .iter()
+        .map(|creator| creator.share as u32)
+        .sum::<u32>();
+    assert_eq!(shares, 100);
+}
+
+#[test]
+fn template_limits_are_enforced() {
+    assert!(template().is_valid());
+    assert!(!MintTemplate {
+        name: String::new(),
+        ..template()
+    }
+    .is_valid());
+    assert!(!MintTemplate {
+        symbol: "TOOLONGSYMBOL".to_string(),
+        ..template()
+    }
+    .is_valid());
+    assert!(!MintTemplate {
+        seller_fee_basis_points: 10_001,
+        ..template()
+    }
+    .is_valid());
+}
+
+#[test]
+fn minted_metadata_is_numbered_into_the_collection() {
+    let collection = Pubkey::new_unique();
+    let metadata = template().metadata(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        7,
+        Some(collection),
+    );
+
+    assert_eq!(metadata.name, "Drip #7");
+    assert_eq!(metadata.uri, "https://example.com/drip/7.json");
+    let minted_collection = metadata.collection.unwrap();
+    assert_eq!(minted_collection.key, collection);
+    assert!(!minted_collection.verified);
+}
+
+#[test]
+fn longest_valid_template_fits_every_tree_leaf() {
+    let template = MintTemplate {
+        name: "N".repeat(20),
+        uri: "u".repeat(185),
+        ..template()
+    };
+    assert!(template.is_valid());
+    assert!(!MintTemplate {
+        name: "N".repeat(21),
+        ..template.clone()
+    }
+    .is_valid());
+
+    let metadata = template.metadata(Pubkey::new_unique(), Pubkey::new_unique(), 1 << 30, None);
+    assert!(metadata.name.len() <= MAX_NAME_LENGTH);
+    assert!(metadata.uri.len() <= MAX_URI_LENGTH);
+}
+
+#[test]
+fn burn_pool_without_rules_admits_its_own_cnfts() {
+    let pool_key = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let mut pool = HybridPoolConfig::default();
+    pool.admit_own_cnfts(pool_key);
+
+    let own = template().metadata(pool_key, authority, 1, None);
+    assert!(verify_admission(&own, &pool).is_ok());
+
+    let other = template().metadata(Pubkey::new_unique(), authority, 1, None);
+    assert_eq!(
+        verify_admission(&other, &pool).unwrap_err(),
+        ErrorCode::InvalidCreator.into()
+    );
+}
+
+#[test]
+fn existing_admission_rules_are_kept() {
+    let pool_key = Pubkey::new_unique();
+    let collection = Pubkey::new_unique();
+    let mut pool = HybridPoolConfig {
+        collections: vec![collection],
+        ..HybridPoolConfig::default()
+    };
+    pool.admit_own_cnfts(pool_key);
+
+    assert!(pool.creators.is_empty());
+    assert_eq!(pool.collections, vec![collection]);
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::{AcceptedToken, HybridPoolConfig, NATIVE_MINT};
+
+fn pool(token: Pubkey, price: u64, accepted_tokens: Vec<AcceptedToken>) -> HybridPoolConfig {
+    HybridPoolConfig {
+        token,
+        price,
+        accepted_tokens,
+        ..HybridPoolConfig::default()
+    }
+}
+
+#[test]
+fn each_accepted_token_has_its_own_price() {
+    let token = Pubkey::new_unique();
+    let partner = Pubkey::new_unique();
+    let pool = pool(
+        token,
+        5_000,
+        vec![
+            AcceptedToken {
+                mint: partner,
+                price: 7_000,
+                deposited: 0,
+            },
+            AcceptedToken {
+                mint: NATIVE_MINT,
+                price: 1_000_000,
+                deposited: 0,
+            },
+        ],
+    );
+
+    assert_eq!(pool.price_for(&token).unwrap(), 5_000);
+    assert_eq!(pool.price_for(&partner).unwrap(), 7_000);
+    assert_eq!(pool.price_for(&NATIVE_MINT).unwrap(), 1_000_000);
+    assert!(pool.accepts(&token));
+    assert!(pool.accepts(&partner));
+    assert!(pool.accepts(&NATIVE_MINT));
+}
+
+#[test]
+fn unknown_mints_are_rejected() {
+    let token = Pubkey::new_unique();
+    let pool = pool(token, 5_000, vec![]);
+    let other = Pubkey::new_unique();
+
+    assert!(!pool.accepts(&other));
+    assert!(!pool.accepts(&NATIVE_MINT));
+    assert_eq!(
+        pool.price_for(&other).unwrap_err(),
+        ErrorCode::UnsupportedToken.into()
+    );
+}
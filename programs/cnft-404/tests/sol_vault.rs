@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::sol_vault::{check_withdrawal, deposit_lamports};
+
+fn is_insufficient(err: anchor_lang::error::Error) -> bool {
+    err == ErrorCode::InsufficientPoolFunds.into()
+}
+
+#[test]
+fn first_deposit_covers_vault_rent() {
+    let rent = Rent::default();
+    let minimum = rent.minimum_balance(0);
+
+    assert_eq!(deposit_lamports(&rent, 0, 1_000).unwrap(), 1_000 + minimum);
+    assert_eq!(deposit_lamports(&rent, 0, 0).unwrap(), minimum);
+    assert_eq!(deposit_lamports(&rent, minimum, 1_000).unwrap(), 1_000);
+    assert_eq!(
+        deposit_lamports(&rent, 0, u64::MAX).unwrap_err(),
+        ErrorCode::MathOverflow.into()
+    );
+}
+
+#[test]
+fn withdrawal_keeps_vault_rent_exempt() {
+    let rent = Rent::default();
+    let minimum = rent.minimum_balance(0);
+    let balance = minimum + 1_000;
+
+    assert!(check_withdrawal(&rent, balance, 1_000).is_ok());
+    assert!(check_withdrawal(&rent, balance, 0).is_ok());
+    assert!(is_insufficient(
+        check_withdrawal(&rent, balance, 1_001).unwrap_err()
+    ));
+    assert!(is_insufficient(
+        check_withdrawal(&rent, balance, balance).unwrap_err()
+    ));
+    assert!(is_insufficient(
+        check_withdrawal(&rent, balance, balance + 1).unwrap_err()
+    ));
+}
@@ -0,0 +1,76 @@
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::random::resolve_positions;
+use drip_rewards::{HybridPoolConfig, Tier};
+
+fn pool(positions: &[u8]) -> HybridPoolConfig {
+    let mut pool = HybridPoolConfig::default();
+    for &pos in positions {
+        pool.add_item(pos);
+    }
+    pool
+}
+
+#[test]
+fn tier_counts_follow_the_inventory() {
+    let mut pool = pool(&[0, 1, 2, 3]);
+    assert_eq!(pool.tier_counts, [4, 0, 0]);
+
+    pool.set_tier(2, Tier::Legendary).unwrap();
+    pool.set_tier(3, Tier::Rare).unwrap();
+    assert_eq!(pool.tier_counts, [2, 1, 1]);
+
+    pool.remove_item(2).unwrap();
+    assert_eq!(pool.tier_counts, [2, 1, 0]);
+    // a drawn position keeps its tier when it comes back
+    pool.return_item(2);
+    assert!(pool.tier_of(2) == Tier::Legendary);
+    assert_eq!(pool.tier_counts, [2, 1, 1]);
+
+    // a new deposit at a reused position starts out common
+    pool.remove_item(3).unwrap();
+    pool.add_item(3);
+    assert!(pool.tier_of(3) == Tier::Common);
+    assert_eq!(pool.tier_counts, [3, 0, 1]);
+
+    let err = pool.set_tier(9, Tier::Rare).unwrap_err();
+    assert_eq!(err, ErrorCode::PositionNotInPool.into());
+}
+
+#[test]
+fn draws_follow_tier_weights() {
+    let mut pool = pool(&[0, 1, 2]);
+    pool.set_tier(2, Tier::Legendary).unwrap();
+    pool.tier_weights = [3, 0, 1];
+
+    // the two common positions take 6 of the 7 weight units
+    let picks: Vec<usize> = (0..7).map(|seed| pool.pick_item(seed).unwrap()).collect();
+    assert_eq!(picks, vec![0, 0, 0, 1, 1, 1, 2]);
+
+    pool.tier_weights = [0, 0, 1];
+    assert!((0..20).all(|seed| pool.pick_item(seed) == Some(2)));
+}
+
+#[test]
+fn zero_weights_draw_uniformly() {
+    let pool = pool(&[4, 5, 6]);
+
+    let picks: Vec<usize> = (0..3).map(|seed| pool.pick_item(seed).unwrap()).collect();
+    assert_eq!(picks, vec![0, 1, 2]);
+    assert_eq!(HybridPoolConfig::default().pick_item(7), None);
+}
+
+#[test]
+fn resolved_draws_follow_tier_weights() {
+    let mut pool = pool(&[0, 1, 2, 3]);
+    pool.set_tier(3, Tier::Legendary).unwrap();
+    pool.tier_weights = [0, 0, 1];
+
+    let slot_hash = [7; 32];
+    assert!((0..20u8).all(|holder| {
+        resolve_positions(&mut pool.clone(), 1, &slot_hash, &[&[holder]]).unwrap() == [3]
+    }));
+    // without replacement, the next draw falls back to the common positions
+    let positions = resolve_positions(&mut pool, 2, &slot_hash, &[]).unwrap();
+    assert_eq!(positions[0], 3);
+    assert_eq!(pool.tier_counts, [2, 0, 0]);
+}
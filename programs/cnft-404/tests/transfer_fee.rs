@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+    TransferFee, TransferFeeConfig,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint;
+use drip_rewards::transfer_fee::transfer_fee;
+
+fn fee(epoch: u64, maximum_fee: u64, basis_points: u16) -> TransferFee {
+    TransferFee {
+        epoch: epoch.into(),
+        maximum_fee: maximum_fee.into(),
+        transfer_fee_basis_points: basis_points.into(),
+    }
+}
+
+fn base_mint() -> Mint {
+    Mint {
+        decimals: 6,
+        is_initialized: true,
+        ..Mint::default()
+    }
+}
+
+/// Token-2022 mint data with a transfer fee of `newer` from its epoch on,
+/// and `older` before it.
+fn fee_mint_data(older: TransferFee, newer: TransferFee) -> Vec<u8> {
+    let len = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+        .unwrap();
+    let mut data = vec![0; len];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+    config.older_transfer_fee = older;
+    config.newer_transfer_fee = newer;
+    state.base = base_mint();
+    state.pack_base();
+    state.init_account_type().unwrap();
+    data
+}
+
+fn fee_for(owner: &Pubkey, data: &mut [u8], epoch: u64, amount: u64) -> u64 {
+    let key = Pubkey::new_unique();
+    let mut lamports = 0;
+    let mint = AccountInfo::new(&key, false, false, &mut lamports, data, owner, false, 0);
+    transfer_fee(&mint, epoch, amount).unwrap()
+}
+
+#[test]
+fn fee_is_rounded_up_and_capped_at_the_maximum() {
+    let mut data = fee_mint_data(fee(0, 0, 0), fee(0, 5_000, 100));
+    let token_2022 = anchor_spl::token_2022::ID;
+
+    assert_eq!(fee_for(&token_2022, &mut data, 0, 0), 0);
+    assert_eq!(fee_for(&token_2022, &mut data, 0, 1), 1);
+    assert_eq!(fee_for(&token_2022, &mut data, 0, 10_000), 100);
+    assert_eq!(fee_for(&token_2022, &mut data, 0, 10_001), 101);
+    assert_eq!(fee_for(&token_2022, &mut data, 0, 500_000), 5_000);
+    assert_eq!(fee_for(&token_2022, &mut data, 0, u64::MAX), 5_000);
+}
+
+#[test]
+fn fee_follows_the_epoch_it_applies_from() {
+    let mut data = fee_mint_data(fee(0, u64::MAX, 10), fee(100, u64::MAX, 200));
+    let token_2022 = anchor_spl::token_2022::ID;
+
+    assert_eq!(fee_for(&token_2022, &mut data, 99, 10_000), 10);
+    assert_eq!(fee_for(&token_2022, &mut data, 100, 10_000), 200);
+}
+
+#[test]
+fn mints_without_the_extension_charge_nothing() {
+    let mut token_mint = vec![0; Mint::LEN];
+    Mint::pack(base_mint(), &mut token_mint).unwrap();
+    assert_eq!(
+        fee_for(&anchor_spl::token::ID, &mut token_mint, 0, 1_000),
+        0
+    );
+
+    let mut plain_2022_mint = token_mint.clone();
+    assert_eq!(
+        fee_for(&anchor_spl::token_2022::ID, &mut plain_2022_mint, 0, 1_000),
+        0
+    );
+
+    // a spl token mint is never read as Token-2022 data
+    let mut fee_data = fee_mint_data(fee(0, u64::MAX, 100), fee(0, u64::MAX, 100));
+    assert_eq!(fee_for(&anchor_spl::token::ID, &mut fee_data, 0, 1_000), 0);
+}
@@ -0,0 +1,377 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::metadata::mpl_token_metadata;
+use drip_rewards::errors::ErrorCode;
+use drip_rewards::validation::{
+    verify_admission, verify_collection, verify_core_admission, verify_leaf,
+    verify_legacy_admission, verify_metadata_rules,
+};
+use drip_rewards::{AnchorMetadataArgs, HybridPoolConfig, MetadataRules, MAX_FEE_BPS};
+use mpl_bubblegum::hash::{hash_creators, hash_metadata};
+use mpl_bubblegum::types::{Collection, Creator, MetadataArgs, TokenProgramVersion};
+use mpl_core::accounts::BaseAssetV1;
+use mpl_core::types::{Key, UpdateAuthority};
+
+fn metadata(collection: Option<Collection>) -> MetadataArgs {
+    MetadataArgs {
+        name: "Drip #1".to_string(),
+        symbol: "DRIP".to_string(),
+        uri: "https://example.com/1.json".to_string(),
+        seller_fee_basis_points: 500,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection,
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: vec![],
+    }
+}
+
+fn pool(collections: Vec<Pubkey>) -> HybridPoolConfig {
+    HybridPoolConfig {
+        collections,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn verified_allowlisted_collection_is_accepted() {
+    let key = Pubkey::new_unique();
+    let metadata = metadata(Some(Collection {
+        verified: true,
+        key,
+    }));
+
+    assert!(verify_collection(&metadata, &pool(vec![key])).is_ok());
+}
+
+#[test]
+fn unverified_collection_is_rejected() {
+    let key = Pubkey::new_unique();
+    let metadata = metadata(Some(Collection {
+        verified: false,
+        key,
+    }));
+
+    let err = verify_collection(&metadata, &pool(vec![key])).unwrap_err();
+    assert_eq!(err, ErrorCode::UnverifiedCollection.into());
+}
+
+#[test]
+fn missing_or_unknown_collection_is_rejected() {
+    let pool = pool(vec![Pubkey::new_unique()]);
+    let unknown = metadata(Some(Collection {
+        verified: true,
+        key: Pubkey::new_unique(),
+    }));
+
+    let err = verify_collection(&metadata(None), &pool).unwrap_err();
+    assert_eq!(err, ErrorCode::InvalidCollection.into());
+    let err = verify_collection(&unknown, &pool).unwrap_err();
+    assert_eq!(err, ErrorCode::InvalidCollection.into());
+}
+
+fn creator(address: Pubkey, verified: bool) -> Creator {
+    Creator {
+        address,
+        verified,
+        share: 100,
+    }
+}
+
+#[test]
+fn verified_allowlisted_creator_is_accepted_without_collection() {
+    let address = Pubkey::new_unique();
+    let mut metadata = metadata(None);
+    metadata.creators = vec![creator(address, true)];
+    let pool = HybridPoolConfig {
+        creators: vec![address],
+        ..Default::default()
+    };
+
+    assert!(verify_admission(&metadata, &pool).is_ok());
+}
+
+#[test]
+fn unverified_creator_is_rejected() {
+    let address = Pubkey::new_unique();
+    let mut metadata = metadata(None);
+    metadata.creators = vec![creator(address, false)];
+    let pool = HybridPoolConfig {
+        creators: vec![address],
+        ..Default::default()
+    };
+
+    let err = verify_admission(&metadata, &pool).unwrap_err();
+    assert_eq!(err, ErrorCode::InvalidCreator.into());
+}
+
+#[test]
+fn wrong_creator_hash_is_rejected_for_every_pool() {
+    let key = Pubkey::new_unique();
+    let address = Pubkey::new_unique();
+    let mut metadata = metadata(Some(Collection {
+        verified: true,
+        key,
+    }));
+    metadata.creators = vec![creator(address, false)];
+    let forged = hash_creators(&metadata.creators);
+
+    // claiming a verified creator the leaf does not have breaks the hash,
+    // even when the pool only checks collections
+    metadata.creators = vec![creator(address, true)];
+    let data_hash = hash_metadata(&metadata).unwrap();
+    let err = verify_leaf(&metadata, &data_hash, &forged, &pool(vec![key])).unwrap_err();
+    assert_eq!(err, ErrorCode::InvalidCreatorHash.into());
+}
+
+#[test]
+fn collection_and_creator_allowlists_are_both_enforced() {
+    let collection = Pubkey::new_unique();
+    let address = Pubkey::new_unique();
+    let pool = HybridPoolConfig {
+        collections: vec![collection],
+        creators: vec![address],
+        ..Default::default()
+    };
+
+    let mut metadata = metadata(None);
+    metadata.creators = vec![creator(address, true)];
+    let err = verify_admission(&metadata, &pool).unwrap_err();
+    assert_eq!(err, ErrorCode::InvalidCollection.into());
+
+    metadata.collection = Some(Collection {
+        verified: true,
+        key: collection,
+    });
+    assert!(verify_admission(&metadata, &pool).is_ok());
+}
+
+#[test]
+fn metadata_rules_filter_symbol_name_uri_and_fee() {
+    let rules = MetadataRules {
+        symbol: "DRIP".to_string(),
+        name_prefix: "Drip #".to_string(),
+        uri_prefix: "https://example.com/".to_string(),
+        min_seller_fee_basis_points: Some(100),
+        max_seller_fee_basis_points: Some(1_000),
+    };
+    assert!(rules.is_valid());
+    assert!(verify_metadata_rules(&metadata(None), &rules).is_ok());
+    assert!(verify_metadata_rules(&metadata(None), &MetadataRules::default()).is_ok());
+
+    let mismatches: [fn(&mut MetadataArgs); 5] = [
+        |m| m.symbol = "DRIPX".to_string(),
+        |m| m.name = "Other #1".to_string(),
+        |m| m.uri = "https://example.com.evil.io/1.json".to_string(),
+        |m| m.seller_fee_basis_points = 50,
+        |m| m.seller_fee_basis_points = 5_000,
+    ];
+    for mismatch in mismatches {
+        let mut metadata = metadata(None);
+        mismatch(&mut metadata);
+        let err = verify_metadata_rules(&metadata, &rules).unwrap_err();
+        assert_eq!(err, ErrorCode::MetadataRuleMismatch.into());
+    }
+}
+
+#[test]
+fn inverted_fee_range_is_invalid() {
+    let rules = MetadataRules {
+        min_seller_fee_basis_points: Some(500),
+        max_seller_fee_basis_points: Some(100),
+        ..Default::default()
+    };
+    assert!(!rules.is_valid());
+}
+
+#[test]
+fn fee_bounds_above_max_fee_bps_are_invalid() {
+    for rules in [
+        MetadataRules {
+            min_seller_fee_basis_points: Some(MAX_FEE_BPS + 1),
+            ..Default::default()
+        },
+        MetadataRules {
+            max_seller_fee_basis_points: Some(MAX_FEE_BPS + 1),
+            ..Default::default()
+        },
+        MetadataRules {
+            min_seller_fee_basis_points: Some(100),
+            max_seller_fee_basis_points: Some(u16::MAX),
+            ..Default::default()
+        },
+    ] {
+        assert!(!rules.is_valid());
+    }
+
+    let rules = MetadataRules {
+        min_seller_fee_basis_points: Some(0),
+        max_seller_fee_basis_points: Some(MAX_FEE_BPS),
+        ..Default::default()
+    };
+    assert!(rules.is_valid());
+}
+
+fn anchor_metadata(collection: Pubkey, creator: Pubkey) -> AnchorMetadataArgs {
+    AnchorMetadataArgs {
+        name: "Drip #1".to_string(),
+        symbol: "DRIP".to_string(),
+        uri: "https://example.com/1.json".to_string(),
+        seller_fee_basis_points: 500,
+        primary_sale_happened: true,
+        is_mutable: false,
+        edition_nonce: Some(254),
+        token_standard: Some(drip_rewards::TokenStandard::NonFungible),
+        collection: Some(drip_rewards::Collection {
+            verified: true,
+            key: collection,
+        }),
+        uses: None,
+        token_program_version: drip_rewards::TokenProgramVersion::Original,
+        creators: vec![drip_rewards::Creator {
+            address: creator,
+            verified: true,
+            share: 100,
+        }],
+    }
+}
+
+// Leaf hashes of `anchor_metadata(COLLECTION, CREATOR)`, computed separately
+// from the Bubblegum leaf encoding rather than through `hash_metadata`:
+// keccak(keccak(borsh metadata) || seller fee le) and
+// keccak(creator || verified || share).
+const COLLECTION: Pubkey = Pubkey::new_from_array([7; 32]);
+const CREATOR: Pubkey = Pubkey::new_from_array([9; 32]);
+const DATA_HASH: [u8; 32] = [
+    0x6f, 0x50, 0x27, 0xa1, 0x37, 0x4a, 0x02, 0x89, 0x6d, 0xa9, 0x72, 0x6b, 0x91, 0x53, 0xce, 0x7e,
+    0x70, 0xf5, 0x44, 0xed, 0xfd, 0x43, 0x88, 0x80, 0xb1, 0xca, 0x30, 0xec, 0x94, 0xa4, 0xb4, 0xd2,
+];
+const CREATOR_HASH: [u8; 32] = [
+    0xe5, 0x86, 0xa1, 0xa7, 0x97, 0x44, 0x19, 0x7c, 0x5f, 0xa0, 0x1c, 0x25, 0x40, 0x55, 0xa5, 0x1d,
+    0x36, 0x2f, 0x87, 0xbb, 0x8c, 0xd8, 0x03, 0x86, 0x00, 0x43, 0xd3, 0xc7, 0x96, 0xf5, 0x84, 0x80,
+];
+
+#[test]
+fn converted_metadata_matches_bubblegum_hashes() {
+    let metadata = MetadataArgs::try_from(anchor_metadata(COLLECTION, CREATOR)).unwrap();
+    assert_eq!(hash_metadata(&metadata).unwrap(), DATA_HASH);
+    assert_eq!(hash_creators(&metadata.creators), CREATOR_HASH);
+
+    let pool = HybridPoolConfig {
+        creators: vec![CREATOR],
+        ..pool(vec![COLLECTION])
+    };
+    assert!(verify_leaf(&metadata, &DATA_HASH, &CREATOR_HASH, &pool).is_ok());
+
+    let mut tampered = DATA_HASH;
+    tampered[0] ^= 1;
+    let err = verify_leaf(&metadata, &tampered, &CREATOR_HASH, &pool).unwrap_err();
+    assert_eq!(err, ErrorCode::InvalidDataHash.into());
+
+    let err = verify_leaf(&metadata, &DATA_HASH, &tampered, &pool).unwrap_err();
+    assert_eq!(err, ErrorCode::InvalidCreatorHash.into());
+}
+
+fn core_asset(update_authority: UpdateAuthority) -> BaseAssetV1 {
+    BaseAssetV1 {
+        key: Key::AssetV1,
+        owner: Pubkey::new_unique(),
+        update_authority,
+        name: "Drip #1".to_string(),
+        uri: "https://example.com/1.json".to_string(),
+        seq: None,
+    }
+}
+
+#[test]
+fn core_asset_is_admitted_by_collection_membership() {
+    let collection = Pubkey::new_unique();
+    let pool = pool(vec![collection]);
+
+    let member = core_asset(UpdateAuthority::Collection(collection));
+    assert!(verify_core_admission(&member, &pool).is_ok());
+
+    for outsider in [
+        UpdateAuthority::Collection(Pubkey::new_unique()),
+        UpdateAuthority::Address(collection),
+        UpdateAuthority::None,
+    ] {
+        let err = verify_core_admission(&core_asset(outsider), &pool).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidCollection.into());
+    }
+}
+
+#[test]
+fn core_asset_must_match_name_and_uri_rules() {
+    let collection = Pubkey::new_unique();
+    let mut pool = pool(vec![collection]);
+    pool.metadata_rules.uri_prefix = "https://drip.haus/".to_string();
+
+    let asset = core_asset(UpdateAuthority::Collection(collection));
+    let err = verify_core_admission(&asset, &pool).unwrap_err();
+    assert_eq!(err, ErrorCode::MetadataRuleMismatch.into());
+}
+
+#[test]
+fn core_asset_is_rejected_by_rules_it_cannot_satisfy() {
+    let collection = Pubkey::new_unique();
+    let asset = core_asset(UpdateAuthority::Collection(collection));
+
+    let mut with_symbol = pool(vec![collection]);
+    with_symbol.metadata_rules.symbol = "DRIP".to_string();
+    let mut with_creators = pool(vec![collection]);
+    with_creators.creators = vec![Pubkey::new_unique()];
+    let mut with_fee_bound = pool(vec![collection]);
+    with_fee_bound.metadata_rules.min_seller_fee_basis_points = Some(500);
+
+    for pool in [with_symbol, with_creators, with_fee_bound] {
+        let err = verify_core_admission(&asset, &pool).unwrap_err();
+        assert_eq!(err, ErrorCode::UnsupportedCoreRule.into());
+    }
+}
+
+fn legacy_metadata(collection: Pubkey) -> mpl_token_metadata::accounts::Metadata {
+    // token metadata pads strings to their maximum length
+    let pad = |value: &str, len: usize| format!("{value:\0<len$}");
+    mpl_token_metadata::accounts::Metadata {
+        key: mpl_token_metadata::types::Key::MetadataV1,
+        update_authority: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        name: pad("Drip #1", 32),
+        symbol: pad("DRIP", 10),
+        uri: pad("https://example.com/1.json", 200),
+        seller_fee_basis_points: 500,
+        creators: None,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(mpl_token_metadata::types::TokenStandard::ProgrammableNonFungible),
+        collection: Some(mpl_token_metadata::types::Collection {
+            verified: true,
+            key: collection,
+        }),
+        uses: None,
+        collection_details: None,
+        programmable_config: None,
+    }
+}
+
+#[test]
+fn legacy_nft_is_admitted_by_trimmed_metadata() {
+    let collection = Pubkey::new_unique();
+    let mut pool = pool(vec![collection]);
+    pool.metadata_rules = MetadataRules {
+        symbol: "DRIP".to_string(),
+        uri_prefix: "https://example.com/".to_string(),
+        ..Default::default()
+    };
+
+    let mut metadata = legacy_metadata(collection);
+    assert!(verify_legacy_admission(&metadata, &pool).is_ok());
+
+    metadata.collection.as_mut().unwrap().verified = false;
+    let err = verify_legacy_admission(&metadata, &pool).unwrap_err();
+    assert_eq!(err, ErrorCode::UnverifiedCollection.into());
+}